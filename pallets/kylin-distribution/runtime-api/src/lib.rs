@@ -0,0 +1,18 @@
+//! Runtime API definition for the kylin-distribution pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	pub trait DistributionApi<AccountId, DistributionId> where
+		AccountId: Codec,
+		DistributionId: Codec,
+	{
+		/// Distributions in which `identity` still has funds to claim.
+		fn distributions_for(identity: AccountId) -> Vec<DistributionId>;
+	}
+}
@@ -1,13 +1,17 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub use models::DistributionState;
 pub use pallet::*;
 
+pub mod migrations;
 pub mod models;
 pub mod weights;
 
-#[cfg(any(feature = "runtime-benchmarks", test))]
+#[cfg(feature = "runtime-benchmarks")]
 mod benchmarking;
 mod mocks;
+#[cfg(test)]
+mod tests;
 
 #[frame_support::pallet]
 pub mod pallet {
@@ -108,7 +112,10 @@ pub trait Distributor {
 	/// [`Balance`](Config::Balance) as configured by the pallet.
 	pub type BalanceOf<T> = <T as Config>::Balance;
 	/// [`RecipientFund`](crate::models::RecipientFund) as configured by the pallet.
-	pub type RecipientFundOf<T> = RecipientFund<<T as Config>::Balance, <T as Config>::Moment>;
+	pub type RecipientFundOf<T> =
+		RecipientFund<<T as Config>::Balance, <T as Config>::Moment, MemoOf<T>>;
+	/// Memo attached to a recipient, bounded by [`MemoLimit`](Config::MemoLimit).
+	pub type MemoOf<T> = BoundedVec<u8, <T as Config>::MemoLimit>;
 	/// [`Moment`](Config::Moment) as configured by the pallet.
 	pub type MomentOf<T> = <T as Config>::Moment;
 	
@@ -123,6 +130,7 @@ pub trait Distributor {
 			distribution_id: T::DistributionId,
 			number: u32,
 			unclaimed_funds: T::Balance,
+			memos: Vec<(T::AccountId, MemoOf<T>)>,
 		},
 		RecipientRemoved {
 			distribution_id: T::DistributionId,
@@ -157,6 +165,10 @@ pub trait Distributor {
 		RecipientAlreadyClaimed,
 		RecipientNotFound,
 		UnclaimedFundsRemaining,
+		VestingPeriodTooLong,
+		InvalidClaimDeadline,
+		ClaimWindowClosed,
+		ClaimWindowOpen,
 	}
 
 	#[pallet::config]
@@ -211,11 +223,26 @@ pub trait Distributor {
 		#[pallet::constant]
 		type Stake: Get<BalanceOf<Self>>;
 
+		/// The longest vesting period a recipient can be added with
+		#[pallet::constant]
+		type MaxVestingPeriod: Get<MomentOf<Self>>;
+
+		/// The longest memo that can be attached to a recipient
+		#[pallet::constant]
+		type MemoLimit: Get<u32>;
+
+		/// Origin allowed to create Distributions without a stake, e.g. root or the council.
+		type SponsorOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
 		/// The implementation of extrinsic weights.
 		type WeightInfo: WeightInfo;
 	}
 
+	/// The current storage version.
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	pub struct Pallet<T>(_);
 
 	/// The counter used to identify Distributions.
@@ -250,6 +277,19 @@ pub trait Distributor {
 	pub type TotalDistributionRecipients<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::DistributionId, u32, ValueQuery>;
 
+	/// Distributions created by [`Config::SponsorOrigin`], which hold no creation stake.
+	#[pallet::storage]
+	#[pallet::getter(fn sponsored)]
+	#[allow(clippy::disallowed_types)] // Allow `frame_support::pallet_prelude::ValueQuery` because default of false is correct
+	pub type Sponsored<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::DistributionId, bool, ValueQuery>;
+
+	/// Accounts receiving the refunds of Distributions, when it isn't their creator.
+	#[pallet::storage]
+	#[pallet::getter(fn refund_accounts)]
+	pub type RefundAccounts<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::DistributionId, T::AccountId, OptionQuery>;
+
 	/// Recipient funds of Distributions stored by the pallet.
 	#[pallet::storage]
 	#[pallet::getter(fn recipient_funds)]
@@ -263,6 +303,13 @@ pub trait Distributor {
 		OptionQuery,
 	>;
 
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			crate::migrations::v1::migrate::<T>()
+		}
+	}
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Create a new Distribution. This requires that the user puts down a stake in PICA.
@@ -270,11 +317,19 @@ pub trait Distributor {
 		/// If `start_at` is `Some(MomentOf<T>)` and the `MomentOf<T>` is greater than the current
 		/// block, the Distribution will be scheduled to start automatically.
 		///
+		/// Funds of removed recipients and funds left unclaimed are sent to `refund_account`,
+		/// or to the creator if it is `None`.
+		///
+		/// If `claim_deadline` is set, recipients can't claim from then on and the creator can
+		/// sweep the unclaimed funds with `sweep_expired`.
+		///
 		/// Can be called by any signed origin.
 		///
 		/// # Parameter Sources
 		/// * `start_at` - user provided, optional
 		/// * `vesting_schedule` - user provided
+		/// * `refund_account` - user provided, optional
+		/// * `claim_deadline` - user provided, optional
 		///
 		/// # Emits
 		/// * `DistributionCreated`
@@ -285,16 +340,57 @@ pub trait Distributor {
 		/// * `DistributionAlreadyStarted` - The Distribution has already started or has been scheduled to
 		/// start
 		/// * `BackToTheFuture` - The provided `start` has already passed
+		/// * `InvalidClaimDeadline` - The provided `claim_deadline` isn't after the start
 		#[pallet::weight(<T as Config>::WeightInfo::create_distribution())]
 		#[transactional]
 		pub fn create_distribution(
 			origin: OriginFor<T>,
 			start_at: Option<MomentOf<T>>,
 			vesting_schedule: MomentOf<T>,
+			refund_account: Option<T::AccountId>,
+			claim_deadline: Option<MomentOf<T>>,
 		) -> DispatchResult {
 			let creator = ensure_signed(origin)?;
 
-			<Self as Distributor>::create_distribution(creator, start_at, vesting_schedule)
+			Self::do_create_distribution(
+				creator,
+				start_at,
+				vesting_schedule,
+				refund_account,
+				claim_deadline,
+				false,
+			)
+		}
+
+		/// Create a new Distribution without requiring a stake from `creator`.
+		///
+		/// Meant for official campaigns. `creator` manages and funds the recipients like for any
+		/// other Distribution, but nothing is withheld from them at creation.
+		///
+		/// Can be called by [`Config::SponsorOrigin`].
+		///
+		/// # Parameter Sources
+		/// * `creator` - user provided
+		/// * `start_at` - user provided, optional
+		/// * `vesting_schedule` - user provided
+		///
+		/// # Emits
+		/// * `DistributionCreated`
+		/// * `DistributionStarted`
+		///
+		/// # Errors
+		/// * `BackToTheFuture` - The provided `start` has already passed
+		#[pallet::weight(<T as Config>::WeightInfo::create_distribution())]
+		#[transactional]
+		pub fn create_distribution_sponsored(
+			origin: OriginFor<T>,
+			creator: T::AccountId,
+			start_at: Option<MomentOf<T>>,
+			vesting_schedule: MomentOf<T>,
+		) -> DispatchResult {
+			T::SponsorOrigin::ensure_origin(origin)?;
+
+			Self::do_create_distribution(creator, start_at, vesting_schedule, None, None, true)
 		}
 
 		/// Add one or more recipients to the Distribution, specifying the token amount that each
@@ -312,12 +408,13 @@ pub trait Distributor {
 		/// # Errors
 		/// * `DistributionDoesNotExist` - No Distribution exist that is associated 'distribution_id'
 		/// * `NotDistributionCreator` - Signer of the origin is not the creator of the Distribution
+		/// * `VestingPeriodTooLong` - A vesting period is above [`Config::MaxVestingPeriod`]
 		#[pallet::weight(<T as Config>::WeightInfo::add_recipient(recipients.len() as u32))]
 		#[transactional]
 		pub fn add_recipient(
 			origin: OriginFor<T>,
 			distribution_id: T::DistributionId,
-			recipients: Vec<(T::AccountId, BalanceOf<T>, MomentOf<T>, bool)>,
+			recipients: Vec<(T::AccountId, BalanceOf<T>, MomentOf<T>, bool, Option<MemoOf<T>>)>,
 		) -> DispatchResult {
 			let origin_id = ensure_signed(origin)?;
 
@@ -418,6 +515,7 @@ pub trait Distributor {
 		/// * `AssociatedWithAnohterAccount` - Associated with a different account
 		/// * `ArithmiticError` - Overflow while totaling claimed funds
 		/// * `RecipientNotFound` - No recipient associated with the `identity` could be found.
+		/// * `ClaimWindowClosed` - The claim deadline of the Distribution has passed
 		#[pallet::weight(<T as Config>::WeightInfo::claim(TotalDistributionRecipients::<T>::get(distribution_id)))]
 		#[transactional]
 		pub fn claim(
@@ -429,6 +527,46 @@ pub trait Distributor {
 			
 			<Self as Distributor>::claim(distribution_id, reward_account.clone(), reward_account)
 		}
+
+		/// End an Distribution whose claim deadline has passed.
+		///
+		/// The stake is returned to the creator and unclaimed funds are sent to the refund
+		/// account.
+		///
+		/// Only callable by the origin that created the Distribution.
+		///
+		/// # Parameter Sources
+		/// * `distribution_id` - user selected, provided by the system
+		///
+		/// # Emits
+		/// * `DistributionEnded`
+		///
+		/// # Errors
+		/// * `DistributionDoesNotExist` - No Distribution exist that is associated 'distribution_id'
+		/// * `NotDistributionCreator` - Signer of the origin is not the creator of the Distribution
+		/// * `ClaimWindowOpen` - The Distribution has no claim deadline, or it hasn't passed yet
+		#[pallet::weight(<T as Config>::WeightInfo::sweep_expired())]
+		#[transactional]
+		pub fn sweep_expired(origin: OriginFor<T>, distribution_id: T::DistributionId) -> DispatchResult {
+			let origin_id = ensure_signed(origin)?;
+			let distribution = Self::get_distribution(&distribution_id)?;
+			ensure!(distribution.creator == origin_id, Error::<T>::NotDistributionCreator);
+			ensure!(Self::is_claim_window_closed(&distribution), Error::<T>::ClaimWindowOpen);
+
+			Distributions::<T>::try_mutate(distribution_id, |distribution| match distribution.as_mut() {
+				Some(distribution) => {
+					distribution.disabled = true;
+					distribution.claimed_funds = distribution.total_funds;
+					Ok(())
+				},
+				None => Err(Error::<T>::DistributionDoesNotExist),
+			})?;
+
+			Self::prune_distribution(distribution_id)?;
+			Self::deposit_event(Event::DistributionEnded { distribution_id, at: T::Time::now() });
+
+			Ok(())
+		}
 	}
 
 	#[pallet::extra_constants]
@@ -475,6 +613,97 @@ pub trait Distributor {
 			})
 		}
 
+		/// The current [`DistributionState`](crate::models::DistributionState) of an Distribution, or
+		/// `None` if no Distribution is associated with `distribution_id`.
+		pub fn distribution_state(distribution_id: T::DistributionId) -> Option<DistributionState> {
+			Self::get_distribution_state(distribution_id).ok()
+		}
+
+		/// Ids of the Distributions in which `identity` has funds left to claim.
+		pub fn distributions_for(identity: T::AccountId) -> Vec<T::DistributionId> {
+			RecipientFunds::<T>::iter()
+				.filter(|(_, recipient, fund)| *recipient == identity && fund.total > fund.claimed)
+				.map(|(distribution_id, _, _)| distribution_id)
+				.collect()
+		}
+
+		/// The creation stake held in the account of an Distribution.
+		pub(crate) fn get_stake(distribution_id: T::DistributionId) -> BalanceOf<T> {
+			if Sponsored::<T>::get(distribution_id) {
+				T::Balance::zero()
+			} else {
+				T::Stake::get()
+			}
+		}
+
+		/// The account refunds of an Distribution are sent to.
+		pub(crate) fn get_refund_account(
+			distribution_id: T::DistributionId,
+			distribution: &DistributionOf<T>,
+		) -> AccountIdOf<T> {
+			RefundAccounts::<T>::get(distribution_id).unwrap_or_else(|| distribution.creator.clone())
+		}
+
+		/// Whether the claim deadline of `distribution` has passed.
+		pub(crate) fn is_claim_window_closed(distribution: &DistributionOf<T>) -> bool {
+			distribution.claim_deadline.map_or(false, |deadline| T::Time::now() >= deadline)
+		}
+
+		/// Creates an Distribution, taking the stake from the creator unless it is `sponsored`.
+		///
+		/// # Errors
+		/// * `BackToTheFuture` - The provided `start` has already passed
+		/// * `InvalidClaimDeadline` - The provided `claim_deadline` isn't after the start
+		pub(crate) fn do_create_distribution(
+			creator_id: AccountIdOf<T>,
+			start: Option<MomentOf<T>>,
+			schedule: MomentOf<T>,
+			refund_account: Option<AccountIdOf<T>>,
+			claim_deadline: Option<MomentOf<T>>,
+			sponsored: bool,
+		) -> DispatchResult {
+			if let Some(deadline) = claim_deadline {
+				ensure!(deadline > T::Time::now(), Error::<T>::InvalidClaimDeadline);
+			}
+
+			let distribution_id = DistributionCount::<T>::increment()?;
+			let distribution_account = Self::get_distribution_account_id(distribution_id);
+
+			// Insert newly created distribution into pallet's list.
+			Distributions::<T>::insert(
+				distribution_id,
+				Distribution {
+					creator: creator_id.clone(),
+					total_funds: T::Balance::zero(),
+					total_recipients: 0,
+					claimed_funds: T::Balance::zero(),
+					start: None,
+					schedule,
+					disabled: false,
+					claim_deadline,
+				},
+			);
+
+			if let Some(refund_account) = refund_account.filter(|account| *account != creator_id) {
+				RefundAccounts::<T>::insert(distribution_id, refund_account);
+			}
+
+			if sponsored {
+				Sponsored::<T>::insert(distribution_id, true);
+			} else {
+				// Transfer stake into distribution specific account.
+				T::RecipientFundAsset::transfer(&creator_id, &distribution_account, T::Stake::get(), false)?;
+			}
+
+			Self::deposit_event(Event::DistributionCreated { distribution_id, by: creator_id });
+
+			if let Some(moment) = start {
+				Self::start_distribution_at(distribution_id, moment)?;
+			}
+
+			Ok(())
+		}
+
 		/// Gets the [`RecipientFund`](crate::models::RecipientFund) of an Distribution that is
 		/// associated with the `identity`.
 		///
@@ -496,6 +725,7 @@ pub trait Distributor {
 		/// * `DistributionAlreadyStarted` - The Distribution has already started or has been scheduled to
 		/// start
 		/// * `BackToTheFuture` - The provided `start` has already passed
+		/// * `InvalidClaimDeadline` - The claim deadline of the Distribution isn't after `start`
 		pub(crate) fn start_distribution_at(
 			distribution_id: T::DistributionId,
 			start: T::Moment,
@@ -506,6 +736,10 @@ pub trait Distributor {
 			// Distribution exist and hasn't started
 			let distribution = Self::get_distribution(&distribution_id)?;
 			ensure!(distribution.start.is_none(), Error::<T>::DistributionAlreadyStarted);
+			ensure!(
+				distribution.claim_deadline.map_or(true, |deadline| deadline > start),
+				Error::<T>::InvalidClaimDeadline
+			);
 
 			// Update Distribution
 			Distributions::<T>::try_mutate(distribution_id, |distribution| match distribution.as_mut() {
@@ -526,6 +760,9 @@ pub trait Distributor {
 		/// The amount that should have been claimed is proportional to the number of **full**
 		/// vesting steps passed.
 		///
+		/// The cumulative amount is rounded down rather than each claim, so rounding never adds
+		/// up across claims: what is left over is paid out once the vesting period is over.
+		///
 		/// # Errors
 		/// * `DistributionDoesNotExist` - No Distribution exist that is associated 'distribution_id'
 		/// * `DistributionIsNotEnabled` - The Distribution has not been enabled
@@ -572,11 +809,14 @@ pub trait Distributor {
 				return Ok(false)
 			}
 
-			// Return remaining funds to the Distribution creator
+			// Return the stake to the Distribution creator and what is left to the refund account
+			let remaining = T::RecipientFundAsset::balance(&distribution_account);
+			let stake = Self::get_stake(distribution_id).min(remaining);
+			T::RecipientFundAsset::transfer(&distribution_account, &distribution.creator, stake, false)?;
 			T::RecipientFundAsset::transfer(
 				&distribution_account,
-				&distribution.creator,
-				T::RecipientFundAsset::balance(&distribution_account),
+				&Self::get_refund_account(distribution_id, &distribution),
+				remaining.saturating_sub(stake),
 				false,
 			)?;
 
@@ -590,6 +830,8 @@ pub trait Distributor {
 			#[allow(deprecated)]
 			Associations::<T>::remove_prefix(distribution_id, None);
 			Distributions::<T>::remove(distribution_id);
+			Sponsored::<T>::remove(distribution_id);
+			RefundAccounts::<T>::remove(distribution_id);
 
 			Ok(true)
 		}
@@ -601,7 +843,7 @@ pub trait Distributor {
 		type DistributionStart = MomentOf<T>;
 		type Balance = BalanceOf<T>;
 		type Recipient = T::AccountId;
-		type RecipientCollection = Vec<(Self::Recipient, BalanceOf<T>, MomentOf<T>, bool)>;
+		type RecipientCollection = Vec<(Self::Recipient, BalanceOf<T>, MomentOf<T>, bool, Option<MemoOf<T>>)>;
 		type VestingSchedule = MomentOf<T>;
 
 		/// Create a new Distribution.
@@ -618,33 +860,7 @@ pub trait Distributor {
 			start: Option<Self::DistributionStart>,
 			schedule: Self::VestingSchedule,
 		) -> DispatchResult {
-			let distribution_id = DistributionCount::<T>::increment()?;
-			let distribution_account = Self::get_distribution_account_id(distribution_id);
-
-			// Insert newly created distribution into pallet's list.
-			Distributions::<T>::insert(
-				distribution_id,
-				Distribution {
-					creator: creator_id.clone(),
-					total_funds: T::Balance::zero(),
-					total_recipients: 0,
-					claimed_funds: T::Balance::zero(),
-					start: None,
-					schedule,
-					disabled: false,
-				},
-			);
-
-			// Transfer stake into distribution specific account.
-			T::RecipientFundAsset::transfer(&creator_id, &distribution_account, T::Stake::get(), false)?;
-
-			Self::deposit_event(Event::DistributionCreated { distribution_id, by: creator_id });
-
-			if let Some(moment) = start {
-				Self::start_distribution_at(distribution_id, moment)?;
-			}
-
-			Ok(())
+			Self::do_create_distribution(creator_id, start, schedule, None, None, false)
 		}
 
 		/// Add one or more recipients to an Distribution.
@@ -658,6 +874,7 @@ pub trait Distributor {
 		/// # Errors
 		/// * `DistributionDoesNotExist` - No Distribution exist that is associated 'distribution_id'
 		/// * `NotDistributionCreator` - Signer of the origin is not the creator of the Distribution
+		/// * `VestingPeriodTooLong` - A vesting period is above [`Config::MaxVestingPeriod`]
 		fn add_recipient(
 			origin_id: Self::AccountId,
 			distribution_id: Self::DistributionId,
@@ -665,12 +882,16 @@ pub trait Distributor {
 		) -> DispatchResult {
 			let distribution = Self::get_distribution(&distribution_id)?;
 			ensure!(distribution.creator == origin_id, Error::<T>::NotDistributionCreator);
+			ensure!(
+				recipients.iter().all(|(_, _, vesting_period, _, _)| *vesting_period <= T::MaxVestingPeriod::get()),
+				Error::<T>::VestingPeriodTooLong
+			);
 
 			// Calculate total funds and recipients local to this transaction
 			let (transaction_funds, transaction_recipients) = recipients.iter().try_fold(
 				(T::Balance::zero(), 0),
 				|(transaction_funds, transaction_recipients),
-				 (_, funds, _, _)|
+				 (_, funds, _, _, _)|
 				 -> Result<(T::Balance, u32), DispatchError> {
 					Ok((transaction_funds.safe_add(funds)?, transaction_recipients.safe_add(&1)?))
 				},
@@ -679,7 +900,7 @@ pub trait Distributor {
 			// Funds currently owned by the Distribution minus the creation stake
 			let current_funds =
 				T::RecipientFundAsset::balance(&Self::get_distribution_account_id(distribution_id))
-					.safe_sub(&T::Stake::get())?;
+					.safe_sub(&Self::get_stake(distribution_id))?;
 			// Total amount of funds to be required by this Distribution
 			let total_funds = distribution.total_funds.safe_add(&transaction_funds)?;
 			let total_recipients = distribution.total_recipients.safe_add(&transaction_recipients)?;
@@ -696,15 +917,20 @@ pub trait Distributor {
 			}
 
 			// Populate `RecipientFunds`
-			recipients.iter().for_each(|(identity, funds, vesting_period, is_funded)| {
+			let mut memos = Vec::new();
+			recipients.into_iter().for_each(|(identity, funds, vesting_period, is_funded, memo)| {
+				if let Some(memo) = &memo {
+					memos.push((identity.clone(), memo.clone()));
+				}
 				RecipientFunds::<T>::insert(
 					distribution_id,
 					identity,
 					RecipientFundOf::<T> {
-						total: *funds,
+						total: funds,
 						claimed: T::Balance::zero(),
-						vesting_period: *vesting_period,
-						funded_claim: *is_funded,
+						vesting_period,
+						funded_claim: is_funded,
+						memo,
 					},
 				);
 			});
@@ -729,6 +955,7 @@ pub trait Distributor {
 				distribution_id,
 				number: transaction_recipients,
 				unclaimed_funds: total_funds.safe_sub(&claimed_funds)?,
+				memos,
 			});
 
 			Ok(())
@@ -736,7 +963,7 @@ pub trait Distributor {
 
 		/// Remove a recipient from an Distribution.
 		///
-		/// Refunds the creator for the value of the recipient fund.
+		/// Refunds the refund account for the value of the recipient fund.
 		///
 		/// # Errors
 		/// * `DistributionDoesNotExist` - No Distribution exist that is associated 'distribution_id'
@@ -760,12 +987,16 @@ pub trait Distributor {
 			);
 
 			// Update Distribution details
-			let (creator, total_funds, claimed_funds) =
+			let (refund_account, total_funds, claimed_funds) =
 				Distributions::<T>::try_mutate(distribution_id, |distribution| match distribution.as_mut() {
 					Some(distribution) => {
 						distribution.total_funds =
 							distribution.total_funds.saturating_sub(recipient_fund.total);
-						Ok((distribution.creator.clone(), distribution.total_funds, distribution.claimed_funds))
+						Ok((
+							Self::get_refund_account(distribution_id, distribution),
+							distribution.total_funds,
+							distribution.claimed_funds,
+						))
 					},
 					None => Err(Error::<T>::DistributionDoesNotExist),
 				})?;
@@ -774,10 +1005,10 @@ pub trait Distributor {
 				*total_distribution_recipients -= 1;
 			});
 
-			// Refund Distribution creator for the recipient fund's value
+			// Refund the recipient fund's value
 			T::RecipientFundAsset::transfer(
 				&distribution_account,
-				&creator,
+				&refund_account,
 				recipient_fund.total,
 				false,
 			)?;
@@ -863,11 +1094,15 @@ pub trait Distributor {
 		/// * `DistributionIsNotEnabled` - The Distribution has not been enabled
 		/// * `ArithmiticError` - Overflow while totaling claimed funds
 		/// * `RecipientNotFound` - No recipient associated with the `identity` could be found.
+		/// * `ClaimWindowClosed` - The claim deadline of the Distribution has passed
 		fn claim(
 			distribution_id: Self::DistributionId,
 			identity: Self::AccountId,
 			reward_account: Self::AccountId,
 		) -> DispatchResultWithPostInfo {
+			let distribution = Self::get_distribution(&distribution_id)?;
+			ensure!(!Self::is_claim_window_closed(&distribution), Error::<T>::ClaimWindowClosed);
+
 			let distribution_account = Self::get_distribution_account_id(distribution_id);
 			let (available_to_claim, recipient_fund) =
 				RecipientFunds::<T>::try_mutate(distribution_id, identity, |fund| {
@@ -884,7 +1119,7 @@ pub trait Distributor {
 							// Update Distribution and fund status
 							fund.claimed = fund.claimed.saturating_add(available_to_claim);
 
-							Ok((available_to_claim, *fund))
+							Ok((available_to_claim, fund.clone()))
 						},
 						None => Err(Error::<T>::RecipientNotFound),
 					}
@@ -939,8 +1174,11 @@ pub trait Distributor {
 					))
 				})?;
 
-				// Validity Error if the distribution has not started
-				if distribution_state != DistributionState::Enabled {
+				// Validity Error if the distribution has not started or its claim window closed
+				if distribution_state != DistributionState::Enabled ||
+					Distributions::<T>::get(distribution_id)
+						.map_or(false, |distribution| Self::is_claim_window_closed(&distribution))
+				{
 					return InvalidTransaction::Custom(ValidityError::NotClaimable as u8).into()
 				}
 
@@ -0,0 +1,86 @@
+//! Storage migrations of the kylin-distribution pallet.
+
+use crate::{
+	models::{Distribution, RecipientFund},
+	Config, Distributions, Pallet, RecipientFunds,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	log,
+	traits::{Get, GetStorageVersion, StorageVersion},
+	weights::Weight,
+};
+use sp_runtime::RuntimeDebug;
+
+/// `Distribution` with an optional claim deadline, `RecipientFund` with an optional memo.
+pub mod v1 {
+	use super::*;
+
+	/// `Distribution` as stored before version 1.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct OldDistribution<AccountId, Balance, Moment> {
+		pub creator: AccountId,
+		pub total_funds: Balance,
+		pub total_recipients: u32,
+		pub claimed_funds: Balance,
+		pub start: Option<Moment>,
+		pub schedule: Moment,
+		pub disabled: bool,
+	}
+
+	type OldDistributionOf<T> = OldDistribution<
+		<T as frame_system::Config>::AccountId,
+		<T as Config>::Balance,
+		<T as Config>::Moment,
+	>;
+
+	/// `RecipientFund` as stored before version 1.
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	pub struct OldRecipientFund<Balance, Period> {
+		pub total: Balance,
+		pub claimed: Balance,
+		pub vesting_period: Period,
+		pub funded_claim: bool,
+	}
+
+	type OldRecipientFundOf<T> = OldRecipientFund<<T as Config>::Balance, <T as Config>::Moment>;
+
+	/// Translate every `Distributions` entry to `DistributionOf<T>`, without a claim deadline, and
+	/// every `RecipientFunds` entry to `RecipientFundOf<T>`, without a memo.
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 1 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut distributions = 0u64;
+		Distributions::<T>::translate::<OldDistributionOf<T>, _>(|_, old| {
+			distributions += 1;
+			Some(Distribution {
+				creator: old.creator,
+				total_funds: old.total_funds,
+				total_recipients: old.total_recipients,
+				claimed_funds: old.claimed_funds,
+				start: old.start,
+				schedule: old.schedule,
+				disabled: old.disabled,
+				claim_deadline: None,
+			})
+		});
+		let mut funds = 0u64;
+		RecipientFunds::<T>::translate::<OldRecipientFundOf<T>, _>(|_, _, old| {
+			funds += 1;
+			Some(RecipientFund {
+				total: old.total,
+				claimed: old.claimed,
+				vesting_period: old.vesting_period,
+				funded_claim: old.funded_claim,
+				memo: None,
+			})
+		});
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!("Migrated {} distributions and {} recipient funds to v1", distributions, funds);
+
+		let translated = distributions + funds;
+		T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+	}
+}
@@ -22,11 +22,13 @@ pub struct Distribution<AccountId, Balance, Moment> {
 	pub schedule: Moment,
 	/// Set `true` if an distribution has been explicitly disabled.
 	pub disabled: bool,
+	/// Moment after which recipients can no longer claim.
+	pub claim_deadline: Option<Moment>,
 }
 
 /// Funds, and related information, to be claimed by an Distribution recipient.
-#[derive(Encode, Decode, PartialEq, Eq, Copy, Clone, TypeInfo, MaxEncodedLen)]
-pub struct RecipientFund<Balance, Period> {
+#[derive(Encode, Decode, PartialEq, Eq, Clone, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct RecipientFund<Balance, Period, Memo> {
 	/// Total funds committed for this recipient.
 	pub total: Balance,
 	/// Amount of the `total` this recipient has claimed.
@@ -35,6 +37,8 @@ pub struct RecipientFund<Balance, Period> {
 	pub vesting_period: Period,
 	/// If claims by this user will be funded by an external pool.
 	pub funded_claim: bool,
+	/// Opaque tag set by the creator, e.g. a contributor reference id.
+	pub memo: Option<Memo>,
 }
 
 /// Current State of an [`Distribution`](Distribution).
@@ -0,0 +1,297 @@
+use crate::{
+	mocks::{
+		Balances, Distribution, ExtBuilder, MockRuntime, RuntimeEvent, RuntimeOrigin, System, Timestamp,
+		ALICE, BOB, CHARLIE, STAKE,
+	},
+	DistributionState, Error, Event,
+};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+fn with_creator(execute: impl FnOnce()) {
+	ExtBuilder { balances: vec![(ALICE, STAKE * 10)] }.build().execute_with(|| {
+		Timestamp::set_timestamp(1_000);
+		execute()
+	})
+}
+
+#[test]
+fn distribution_state_follows_manual_lifecycle() {
+	with_creator(|| {
+		assert_eq!(Distribution::distribution_state(1), None);
+
+		assert_ok!(Distribution::create_distribution(RuntimeOrigin::signed(ALICE), None, 100, None, None));
+		assert_eq!(Distribution::distribution_state(1), Some(DistributionState::Created));
+
+		assert_ok!(Distribution::enable_distribution(RuntimeOrigin::signed(ALICE), 1));
+		assert_eq!(Distribution::distribution_state(1), Some(DistributionState::Enabled));
+
+		// Disabling leaves no unclaimed funds, so the Distribution is pruned right away.
+		assert_ok!(Distribution::disable_distribution(RuntimeOrigin::signed(ALICE), 1));
+		assert_eq!(Distribution::distribution_state(1), None);
+	});
+}
+
+#[test]
+fn distribution_state_follows_scheduled_start() {
+	with_creator(|| {
+		assert_ok!(Distribution::create_distribution(RuntimeOrigin::signed(ALICE), Some(5_000), 100, None, None));
+		assert_eq!(Distribution::distribution_state(1), Some(DistributionState::Created));
+
+		Timestamp::set_timestamp(5_000);
+		assert_eq!(Distribution::distribution_state(1), Some(DistributionState::Enabled));
+	});
+}
+
+#[test]
+fn distribution_state_reports_disabled_until_pruned() {
+	with_creator(|| {
+		assert_ok!(Distribution::create_distribution(RuntimeOrigin::signed(ALICE), None, 100, None, None));
+		assert_ok!(Distribution::add_recipient(RuntimeOrigin::signed(ALICE), 1, vec![(BOB, 1_000, 100, false, None)]));
+		crate::Distributions::<MockRuntime>::mutate(1, |distribution| {
+			distribution.as_mut().unwrap().disabled = true
+		});
+
+		assert_eq!(Distribution::distribution_state(1), Some(DistributionState::Disabled));
+	});
+}
+
+#[test]
+fn sponsored_distribution_does_not_charge_creator() {
+	with_creator(|| {
+		assert_noop!(
+			Distribution::create_distribution_sponsored(RuntimeOrigin::signed(ALICE), ALICE, None, 100),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(Distribution::create_distribution_sponsored(RuntimeOrigin::root(), ALICE, None, 100));
+		assert_eq!(Balances::free_balance(ALICE), STAKE * 10);
+		assert_eq!(Balances::free_balance(Distribution::get_distribution_account_id(1)), 0);
+		assert_eq!(Distribution::distribution_state(1), Some(DistributionState::Created));
+	});
+}
+
+#[test]
+fn sponsored_distribution_prunes_without_stake() {
+	with_creator(|| {
+		assert_ok!(Distribution::create_distribution_sponsored(RuntimeOrigin::root(), ALICE, None, 100));
+		assert_ok!(Distribution::add_recipient(RuntimeOrigin::signed(ALICE), 1, vec![(BOB, 1_000, 100, false, None)]));
+		assert_eq!(Balances::free_balance(ALICE), STAKE * 10 - 1_000);
+
+		assert_ok!(Distribution::enable_distribution(RuntimeOrigin::signed(ALICE), 1));
+		assert_ok!(Distribution::disable_distribution(RuntimeOrigin::signed(ALICE), 1));
+
+		assert_eq!(Distribution::distribution_state(1), None);
+		assert!(!Distribution::sponsored(1));
+		assert_eq!(Balances::free_balance(ALICE), STAKE * 10);
+	});
+}
+
+#[test]
+fn removed_recipient_is_refunded_to_refund_account() {
+	with_creator(|| {
+		assert_ok!(Distribution::create_distribution(RuntimeOrigin::signed(ALICE), None, 100, Some(CHARLIE), None));
+		assert_eq!(Distribution::refund_accounts(1), Some(CHARLIE));
+		assert_ok!(Distribution::add_recipient(RuntimeOrigin::signed(ALICE), 1, vec![(BOB, 1_000, 100, false, None)]));
+
+		// Nothing is left to claim afterwards, so the Distribution is pruned and the stake returned.
+		assert_ok!(Distribution::remove_recipient(RuntimeOrigin::signed(ALICE), 1, BOB));
+		assert_eq!(Balances::free_balance(CHARLIE), 1_000);
+		assert_eq!(Balances::free_balance(ALICE), STAKE * 10 - 1_000);
+		assert_eq!(Distribution::refund_accounts(1), None);
+	});
+}
+
+#[test]
+fn unclaimed_funds_are_refunded_to_refund_account() {
+	with_creator(|| {
+		assert_ok!(Distribution::create_distribution(RuntimeOrigin::signed(ALICE), None, 100, Some(CHARLIE), None));
+		assert_ok!(Distribution::add_recipient(RuntimeOrigin::signed(ALICE), 1, vec![(BOB, 1_000, 100, false, None)]));
+
+		assert_ok!(Distribution::enable_distribution(RuntimeOrigin::signed(ALICE), 1));
+		assert_ok!(Distribution::disable_distribution(RuntimeOrigin::signed(ALICE), 1));
+
+		assert_eq!(Distribution::distribution_state(1), None);
+		assert_eq!(Balances::free_balance(CHARLIE), 1_000);
+		assert_eq!(Balances::free_balance(ALICE), STAKE * 10 - 1_000);
+	});
+}
+
+#[test]
+fn add_recipient_rejects_vesting_period_over_maximum() {
+	with_creator(|| {
+		assert_ok!(Distribution::create_distribution(RuntimeOrigin::signed(ALICE), None, 100, None, None));
+
+		assert_noop!(
+			Distribution::add_recipient(
+				RuntimeOrigin::signed(ALICE),
+				1,
+				vec![(BOB, 1_000, 1_000, false, None), (CHARLIE, 1_000, 1_001, false, None)]
+			),
+			Error::<MockRuntime>::VestingPeriodTooLong
+		);
+
+		assert_ok!(Distribution::add_recipient(
+			RuntimeOrigin::signed(ALICE),
+			1,
+			vec![(BOB, 1_000, 1_000, false, None), (CHARLIE, 1_000, 500, false, None)]
+		));
+		assert_eq!(Distribution::total_distribution_recipients(1), 2);
+	});
+}
+
+#[test]
+fn distributions_for_lists_distributions_with_unclaimed_funds() {
+	with_creator(|| {
+		for _ in 0..3 {
+			assert_ok!(Distribution::create_distribution(RuntimeOrigin::signed(ALICE), None, 100, None, None));
+		}
+		assert_ok!(Distribution::add_recipient(RuntimeOrigin::signed(ALICE), 1, vec![(BOB, 1_000, 100, false, None)]));
+		assert_ok!(Distribution::add_recipient(RuntimeOrigin::signed(ALICE), 2, vec![(CHARLIE, 1_000, 100, false, None)]));
+		assert_ok!(Distribution::add_recipient(
+			RuntimeOrigin::signed(ALICE),
+			3,
+			vec![(BOB, 1_000, 100, false, None), (CHARLIE, 1_000, 100, false, None)]
+		));
+
+		let mut ids = Distribution::distributions_for(BOB);
+		ids.sort();
+		assert_eq!(ids, vec![1, 3]);
+		assert!(Distribution::distributions_for(ALICE).is_empty());
+	});
+}
+
+#[test]
+fn add_recipient_stores_and_emits_memo() {
+	with_creator(|| {
+		let memo: crate::MemoOf<MockRuntime> = b"ref-42".to_vec().try_into().unwrap();
+		assert_ok!(Distribution::create_distribution(RuntimeOrigin::signed(ALICE), None, 100, None, None));
+		assert_ok!(Distribution::add_recipient(
+			RuntimeOrigin::signed(ALICE),
+			1,
+			vec![(BOB, 1_000, 100, false, Some(memo.clone())), (CHARLIE, 1_000, 100, false, None)]
+		));
+
+		assert_eq!(Distribution::recipient_funds(1, BOB).unwrap().memo, Some(memo.clone()));
+		assert_eq!(Distribution::recipient_funds(1, CHARLIE).unwrap().memo, None);
+		System::assert_last_event(RuntimeEvent::Distribution(Event::RecipientsAdded {
+			distribution_id: 1,
+			number: 2,
+			unclaimed_funds: 2_000,
+			memos: vec![(BOB, memo)],
+		}));
+	});
+}
+
+#[test]
+fn claim_deadline_must_follow_start() {
+	with_creator(|| {
+		assert_noop!(
+			Distribution::create_distribution(RuntimeOrigin::signed(ALICE), None, 100, None, Some(500)),
+			Error::<MockRuntime>::InvalidClaimDeadline
+		);
+		assert_noop!(
+			Distribution::create_distribution(RuntimeOrigin::signed(ALICE), Some(5_000), 100, None, Some(3_000)),
+			Error::<MockRuntime>::InvalidClaimDeadline
+		);
+
+		assert_ok!(Distribution::create_distribution(RuntimeOrigin::signed(ALICE), None, 100, None, Some(3_000)));
+		Timestamp::set_timestamp(3_000);
+		assert_noop!(
+			Distribution::enable_distribution(RuntimeOrigin::signed(ALICE), 1),
+			Error::<MockRuntime>::InvalidClaimDeadline
+		);
+	});
+}
+
+#[test]
+fn claims_close_at_deadline_and_sweep_refunds_the_rest() {
+	with_creator(|| {
+		assert_ok!(Distribution::create_distribution(RuntimeOrigin::signed(ALICE), None, 100, None, Some(5_000)));
+		assert_ok!(Distribution::add_recipient(RuntimeOrigin::signed(ALICE), 1, vec![(BOB, 1_000, 200, false, None)]));
+		assert_ok!(Distribution::enable_distribution(RuntimeOrigin::signed(ALICE), 1));
+
+		Timestamp::set_timestamp(1_100);
+		assert_ok!(Distribution::claim(RuntimeOrigin::none(), 1, BOB));
+		assert_eq!(Balances::free_balance(BOB), 500);
+		assert_noop!(
+			Distribution::sweep_expired(RuntimeOrigin::signed(ALICE), 1),
+			Error::<MockRuntime>::ClaimWindowOpen
+		);
+
+		Timestamp::set_timestamp(5_000);
+		assert_noop!(
+			Distribution::claim(RuntimeOrigin::none(), 1, BOB),
+			Error::<MockRuntime>::ClaimWindowClosed
+		);
+		assert_noop!(
+			Distribution::sweep_expired(RuntimeOrigin::signed(BOB), 1),
+			Error::<MockRuntime>::NotDistributionCreator
+		);
+
+		assert_ok!(Distribution::sweep_expired(RuntimeOrigin::signed(ALICE), 1));
+		assert_eq!(Distribution::distribution_state(1), None);
+		assert_eq!(Balances::free_balance(ALICE), STAKE * 10 - 500);
+		assert_eq!(Balances::free_balance(Distribution::get_distribution_account_id(1)), 0);
+	});
+}
+
+#[test]
+fn claims_over_full_vesting_period_sum_to_fund_total() {
+	with_creator(|| {
+		assert_ok!(Distribution::create_distribution(RuntimeOrigin::signed(ALICE), None, 7, None, None));
+		assert_ok!(Distribution::add_recipient(RuntimeOrigin::signed(ALICE), 1, vec![(BOB, 1_000, 999, false, None)]));
+		assert_ok!(Distribution::enable_distribution(RuntimeOrigin::signed(ALICE), 1));
+
+		for now in (1_007..1_999).step_by(7) {
+			Timestamp::set_timestamp(now);
+			assert_ok!(Distribution::claim(RuntimeOrigin::none(), 1, BOB));
+		}
+		// Every window rounds down, 994/999 of the fund vested by now.
+		assert_eq!(Balances::free_balance(BOB), 994);
+
+		Timestamp::set_timestamp(1_999);
+		assert_ok!(Distribution::claim(RuntimeOrigin::none(), 1, BOB));
+		assert_eq!(Balances::free_balance(BOB), 1_000);
+		// Nothing is stranded, so the fully claimed Distribution is pruned.
+		assert_eq!(Distribution::distribution_state(1), None);
+	});
+}
+
+#[test]
+fn migration_to_v1_adds_no_claim_deadline_nor_memo() {
+	use crate::migrations::v1::{OldDistribution, OldRecipientFund};
+	use frame_support::{
+		storage::unhashed,
+		traits::{GetStorageVersion, Hooks, StorageVersion},
+	};
+
+	with_creator(|| {
+		let old = OldDistribution {
+			creator: ALICE,
+			total_funds: 1_000u128,
+			total_recipients: 1,
+			claimed_funds: 0u128,
+			start: Some(2_000u64),
+			schedule: 100u64,
+			disabled: false,
+		};
+		unhashed::put(&crate::Distributions::<MockRuntime>::hashed_key_for(1), &old);
+		let old = OldRecipientFund { total: 1_000u128, claimed: 0u128, vesting_period: 100u64, funded_claim: true };
+		unhashed::put(&crate::RecipientFunds::<MockRuntime>::hashed_key_for(1, BOB), &old);
+		StorageVersion::new(0).put::<Distribution>();
+
+		Distribution::on_runtime_upgrade();
+
+		let distribution = Distribution::distributions(1).unwrap();
+		assert_eq!(distribution.creator, ALICE);
+		assert_eq!(distribution.total_funds, 1_000);
+		assert_eq!(distribution.start, Some(2_000));
+		assert_eq!(distribution.claim_deadline, None);
+		let fund = Distribution::recipient_funds(1, BOB).unwrap();
+		assert_eq!(fund.total, 1_000);
+		assert!(fund.funded_claim);
+		assert_eq!(fund.memo, None);
+		assert_eq!(Distribution::on_chain_storage_version(), 1);
+	});
+}
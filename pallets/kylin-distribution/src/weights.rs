@@ -11,6 +11,7 @@ pub trait WeightInfo {
 	fn enable_distribution() -> Weight;
 	fn disable_distribution() -> Weight;
 	fn claim(x: u32) -> Weight;
+	fn sweep_expired() -> Weight;
 }
 
 pub struct SubstrateWeight<T>(PhantomData<T>);
@@ -50,5 +51,11 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 		.saturating_add(T::DbWeight::get().reads(3 as u64))
 		.saturating_add(T::DbWeight::get().writes(3 as u64))
 	}
+
+	fn sweep_expired() -> Weight {
+		Weight::from_ref_time(66_168_000)
+		.saturating_add(T::DbWeight::get().reads(3 as u64))
+		.saturating_add(T::DbWeight::get().writes(3 as u64))
+	}
 }
 
@@ -25,6 +25,9 @@ enum KylinFeedFunc {
     xcm_feed_back { 
         key: Vec<u8>,
 		value: i64,
+		decimals: u8,
+		pair: Option<(Vec<u8>, Vec<u8>)>,
+		confidence: Option<Permill>,
     },
 }
 
@@ -211,9 +214,16 @@ impl<T: Config> Pallet<T>
         Ok(())
     }
 
-    pub fn sendback_query_res(para_id: ParaId, key: Vec<u8>, value: i64) -> DispatchResult {
+    pub fn sendback_query_res(
+        para_id: ParaId,
+        key: Vec<u8>,
+        value: i64,
+        decimals: u8,
+        pair: Option<(Vec<u8>, Vec<u8>)>,
+        confidence: Option<Permill>,
+    ) -> DispatchResult {
         let remark = KylinXcmCall::KylinFeed(KylinFeedFunc::xcm_feed_back{
-            key, value,
+            key, value, decimals, pair, confidence,
         });
         T::XcmSender::send_xcm(
             (
@@ -577,7 +577,7 @@ pub mod pallet {
 			let key_limit: OracleKeyOf<T> = key.clone().try_into().map_err(
 				|_| Error::<T>::StorageOverflow
 			)?;
-			kylin_oracle::Pallet::<T>::do_submit_api(cid, key_limit, url.clone(), vpath.clone())?;
+			kylin_oracle::Pallet::<T>::do_submit_api(cid, key_limit, url.clone(), vpath.clone(), None, None, None, None)?;
 
 			let mdata = MetaData { key, url, vpath };
 			let meta_str = serde_json::to_string(&mdata).map_err(|_| Error::<T>::JsonError)?;
@@ -630,7 +630,7 @@ pub mod pallet {
 			let key_limit: OracleKeyOf<T> = key.clone().try_into().map_err(
 				|_| Error::<T>::StorageOverflow
 			)?;
-			kylin_oracle::Pallet::<T>::do_submit_api(cid, key_limit, url.clone(), vpath.clone())?;
+			kylin_oracle::Pallet::<T>::do_submit_api(cid, key_limit, url.clone(), vpath.clone(), None, None, None, None)?;
 
 			let mdata = MetaData { key, url, vpath };
 			let meta_str = serde_json::to_string(&mdata).map_err(|_| Error::<T>::JsonError)?;
@@ -759,8 +759,11 @@ pub mod pallet {
 			let key: OracleKeyOf<T> = mdata.key.clone().try_into().map_err(
 				|_| Error::<T>::StorageOverflow
 			)?;
-			if let Some(val) = kylin_oracle::Pallet::<T>::get(&key) {
-                Self::sendback_query_res(para_id, mdata.key, val.value)
+			if let Some((val, decimals)) = kylin_oracle::Pallet::<T>::get_with_decimals(&key) {
+                let pair = kylin_oracle::Pallet::<T>::value_pairs(&key)
+                    .map(|pair| (pair.base.into_inner(), pair.quote.into_inner()));
+                let confidence = kylin_oracle::Pallet::<T>::value_confidence(&key);
+                Self::sendback_query_res(para_id, mdata.key, val.value, decimals, pair, confidence)
             } else {
                 Err(DispatchError::CannotLookup)
             }
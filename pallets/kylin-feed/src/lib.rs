@@ -112,6 +112,21 @@ pub mod pallet {
     #[pallet::getter(fn values)]
     pub type Values<T: Config> = StorageMap<_, Twox64Concat, KeyLimitOf<T>, TimestampedValue>;
 
+	/// Decimal places of each value in `Values`, as reported by the oracle
+	#[pallet::storage]
+    #[pallet::getter(fn value_decimals)]
+    pub type ValueDecimals<T: Config> = StorageMap<_, Twox64Concat, KeyLimitOf<T>, u8>;
+
+	/// Base and quote asset of each value in `Values`, if the oracle reported one
+	#[pallet::storage]
+    #[pallet::getter(fn value_pairs)]
+    pub type ValuePairs<T: Config> = StorageMap<_, Twox64Concat, KeyLimitOf<T>, (KeyLimitOf<T>, KeyLimitOf<T>)>;
+
+	/// Operator agreement on each value in `Values`, if the oracle reported one
+	#[pallet::storage]
+    #[pallet::getter(fn value_confidence)]
+    pub type ValueConfidence<T: Config> = StorageMap<_, Twox64Concat, KeyLimitOf<T>, Permill>;
+
 	#[pallet::config]
 	pub trait Config: frame_system::Config {
 		type RuntimeOrigin: From<<Self as SystemConfig>::RuntimeOrigin>
@@ -143,6 +158,9 @@ pub mod pallet {
 		QueryFeedBack {
 			key: Vec<u8>,
 			value: TimestampedValue,
+			decimals: u8,
+			pair: Option<(Vec<u8>, Vec<u8>)>,
+			confidence: Option<Permill>,
 		},
 	}
 
@@ -324,11 +342,21 @@ pub mod pallet {
 		/// # Parameter:
 		/// * `key` - key for the feed
 		/// * `value` - value for the feed
+		/// * `decimals` - decimal places of `value`
+		/// * `pair` - base and quote asset of `value`, if its feed was tagged
+		/// * `confidence` - operator agreement on `value`, if the oracle recorded one
 		/// 
 		/// # Emits
 		/// * `QueryFeedBack`
-		#[pallet::weight(T::DbWeight::get().reads_writes(1,1).ref_time().saturating_add(10_000))]
-		pub fn xcm_feed_back(origin: OriginFor<T>, key: Vec<u8>, value: i64) -> DispatchResult {
+		#[pallet::weight(T::DbWeight::get().reads_writes(1,4).ref_time().saturating_add(10_000))]
+		pub fn xcm_feed_back(
+			origin: OriginFor<T>,
+			key: Vec<u8>,
+			value: i64,
+			decimals: u8,
+			pair: Option<(Vec<u8>, Vec<u8>)>,
+			confidence: Option<Permill>,
+		) -> DispatchResult {
             let para_id = ensure_sibling_para(<T as Config>::RuntimeOrigin::from(origin))?;
 
             let now = T::UnixTime::now().as_millis();
@@ -338,8 +366,26 @@ pub mod pallet {
             };
 
             let keylimit: KeyLimitOf<T> = key.clone().try_into().map_err(|_| Error::<T>::StorageOverflow)?;
-            <Values<T>>::insert(keylimit, tval);
-            Self::deposit_event(Event::QueryFeedBack { key, value: tval });
+            let pair_limit: Option<(KeyLimitOf<T>, KeyLimitOf<T>)> = pair
+                .clone()
+                .map(|(base, quote)| -> Result<_, Error<T>> {
+                    Ok((
+                        base.try_into().map_err(|_| Error::<T>::StorageOverflow)?,
+                        quote.try_into().map_err(|_| Error::<T>::StorageOverflow)?,
+                    ))
+                })
+                .transpose()?;
+            <Values<T>>::insert(&keylimit, tval);
+            <ValueDecimals<T>>::insert(&keylimit, decimals);
+            match pair_limit {
+                Some(pair) => <ValuePairs<T>>::insert(&keylimit, pair),
+                None => <ValuePairs<T>>::remove(&keylimit),
+            }
+            match confidence {
+                Some(confidence) => <ValueConfidence<T>>::insert(&keylimit, confidence),
+                None => <ValueConfidence<T>>::remove(&keylimit),
+            }
+            Self::deposit_event(Event::QueryFeedBack { key, value: tval, decimals, pair, confidence });
             Ok(())
         }
 
@@ -0,0 +1,50 @@
+//! Runtime API definition for the kylin-oracle pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::too_many_arguments)]
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+	pub trait KylinOracleApi<Balance, CreatorId, ApiFeed, ApiFeedInfo, FeedHealth, TimestampedValue, OracleSnapshot> where
+		Balance: Codec,
+		CreatorId: Codec,
+		ApiFeed: Codec,
+		ApiFeedInfo: Codec,
+		FeedHealth: Codec,
+		TimestampedValue: Codec,
+		OracleSnapshot: Codec,
+	{
+		/// Staleness of every combined value at `now` (in millis), sorted by key.
+		fn feed_lags(now: u128) -> Vec<(Vec<u8>, u128)>;
+
+		/// Estimated fee of answering an XCM query for `key`, from any parachain.
+		///
+		/// Returns `None` if `key` is longer than the oracle accepts.
+		fn query_response_fee(key: Vec<u8>) -> Option<Balance>;
+
+		/// Every feed registered by `creator`, keyed by oracle key.
+		fn feeds_of(creator: CreatorId) -> Vec<(Vec<u8>, ApiFeed)>;
+
+		/// Source of the feed `key` registered by `creator`, or `None` if there's no such feed.
+		fn feed_config(creator: CreatorId, key: Vec<u8>) -> Option<ApiFeedInfo>;
+
+		/// Fetch health of every feed, per reporting operator.
+		fn oracle_diagnostics() -> Vec<(CreatorId, Vec<u8>, FeedHealth)>;
+
+		/// Combined value of `key` along with every operator's raw value, sorted by creator.
+		fn get_with_raws(key: Vec<u8>) -> (Option<TimestampedValue>, Vec<(CreatorId, TimestampedValue)>);
+
+		/// Combined value of `key` along with the operators' agreement on it, in parts per million.
+		fn get_with_confidence(key: Vec<u8>) -> Option<(TimestampedValue, Option<u32>)>;
+
+		/// Combined value of `key` along with its base and quote asset tags, if the feed is tagged.
+		fn get_with_pair(key: Vec<u8>) -> Option<(TimestampedValue, Option<(Vec<u8>, Vec<u8>)>)>;
+
+		/// Feeds, combined values and raw values of up to `max_keys` keys after `from`,
+		/// sorted by key. Follow `next` of the result for the following page.
+		fn snapshot(from: Option<Vec<u8>>, max_keys: u32) -> OracleSnapshot;
+	}
+}
@@ -1,10 +1,22 @@
-use crate::{Config, TimestampedValueT, OracleKeyOf};
-use frame_support::traits::{Get, UnixTime};
+use crate::{Config, CreatorId, OracleKeyOf, Pallet, TimestampedValue, TimestampedValueT};
+use codec::{Decode, Encode, MaxEncodedLen};
+use frame_support::traits::{Get, SortedMembers, UnixTime};
 use orml_traits::CombineData;
+use scale_info::TypeInfo;
+use sp_runtime::{traits::Convert, Perbill, RuntimeDebug};
 use sp_std::{marker, prelude::*};
 use hex::ToHex;
 
-/// Sort by value and returns median timestamped value.
+/// How a `Config::CombineData` arrives at its value, for the provenance of published values.
+pub trait CombineKind {
+	/// Whether the combined value is derived from every value rather than picked among them,
+	/// making every combined operator a contributor.
+	fn is_averaging() -> bool {
+		false
+	}
+}
+
+/// Sort by value (then timestamp) and returns median timestamped value.
 /// Returns prev_value if not enough valid values.
 pub struct DefaultCombineData<T, MinimumCount, ExpiresIn>(marker::PhantomData<(T, MinimumCount, ExpiresIn)>);
 
@@ -32,9 +44,382 @@ where
 			return prev_value;
 		}
 
+		// Stable sort on `(value, timestamp)` so equal values always resolve the same way.
+		values.sort();
 		let mid_index = count / 2;
 		// Won't panic as `values` ensured not empty.
-		let (_, value, _) = values.select_nth_unstable_by(mid_index as usize, |a, b| a.value.cmp(&b.value));
-		Some(value.clone())
+		Some(values[mid_index as usize])
+	}
+}
+
+impl<T, MinimumCount, ExpiresIn> CombineKind for DefaultCombineData<T, MinimumCount, ExpiresIn> {}
+
+/// Finds the largest cluster of values lying within `Tolerance` of each other and returns the
+/// cluster's median, but only if the cluster holds at least `Threshold` (rounded up) of all oracle
+/// members. Returns `None` when no cluster reaches that supermajority.
+pub struct SupermajorityCombineData<T, Tolerance, Threshold, ExpiresIn>(
+	marker::PhantomData<(T, Tolerance, Threshold, ExpiresIn)>,
+);
+
+impl<T, Tolerance, Threshold, ExpiresIn> CombineData<OracleKeyOf<T>, TimestampedValueT>
+	for SupermajorityCombineData<T, Tolerance, Threshold, ExpiresIn>
+where
+	T: Config,
+	T::AccountId: AsRef<[u8]> + ToHex,
+	Tolerance: Get<i64>,
+	Threshold: Get<Perbill>,
+	ExpiresIn: Get<u128>,
+{
+	fn combine_data(
+		_key: &OracleKeyOf<T>,
+		mut values: Vec<TimestampedValueT>,
+		_prev_value: Option<TimestampedValueT>,
+	) -> Option<TimestampedValueT> {
+		let expires_in = ExpiresIn::get();
+		let now = T::UnixTime::now().as_millis();
+
+		values.retain(|x| x.timestamp + expires_in > now);
+		values.sort();
+
+		// Sliding window over the sorted values; the first widest window wins.
+		let tolerance = Tolerance::get();
+		let (mut best_start, mut best_len) = (0, 0);
+		let mut end = 0;
+		for start in 0..values.len() {
+			end = end.max(start);
+			while end < values.len() && values[end].value.saturating_sub(values[start].value) <= tolerance {
+				end += 1;
+			}
+			if end - start > best_len {
+				best_start = start;
+				best_len = end - start;
+			}
+		}
+
+		let operators = T::Members::sorted_members().len() as u32;
+		let required = Threshold::get().mul_ceil(operators);
+		if best_len == 0 || (best_len as u32) < required {
+			return None;
+		}
+
+		Some(values[best_start + best_len / 2])
+	}
+}
+
+impl<T, Tolerance, Threshold, ExpiresIn> CombineKind
+	for SupermajorityCombineData<T, Tolerance, Threshold, ExpiresIn>
+{
+}
+
+/// Sorts the values, drops `TrimFraction` (rounded down) of them from each end and returns the
+/// mean of the rest, stamped with their newest timestamp. Returns `None` if fewer than
+/// `MinimumCount` values remain.
+pub struct TrimmedMeanCombineData<T, TrimFraction, MinimumCount, ExpiresIn>(
+	marker::PhantomData<(T, TrimFraction, MinimumCount, ExpiresIn)>,
+);
+
+impl<T, TrimFraction, MinimumCount, ExpiresIn> CombineData<OracleKeyOf<T>, TimestampedValueT>
+	for TrimmedMeanCombineData<T, TrimFraction, MinimumCount, ExpiresIn>
+where
+	T: Config,
+	T::AccountId: AsRef<[u8]> + ToHex,
+	TrimFraction: Get<Perbill>,
+	MinimumCount: Get<u32>,
+	ExpiresIn: Get<u128>,
+{
+	fn combine_data(
+		_key: &OracleKeyOf<T>,
+		mut values: Vec<TimestampedValueT>,
+		_prev_value: Option<TimestampedValueT>,
+	) -> Option<TimestampedValueT> {
+		let expires_in = ExpiresIn::get();
+		let now = T::UnixTime::now().as_millis();
+
+		values.retain(|x| x.timestamp + expires_in > now);
+		values.sort();
+
+		let trim = TrimFraction::get().mul_floor(values.len() as u32) as usize;
+		let kept = &values[trim..values.len() - trim];
+		if kept.is_empty() || (kept.len() as u32) < MinimumCount::get() {
+			return None;
+		}
+
+		let sum: i128 = kept.iter().map(|x| x.value as i128).sum();
+		let timestamp = kept.iter().map(|x| x.timestamp).max().unwrap_or_default();
+		Some(TimestampedValue { value: (sum / kept.len() as i128) as i64, timestamp })
+	}
+}
+
+impl<T, TrimFraction, MinimumCount, ExpiresIn> CombineKind
+	for TrimmedMeanCombineData<T, TrimFraction, MinimumCount, ExpiresIn>
+{
+	fn is_averaging() -> bool {
+		true
+	}
+}
+
+/// Mean of the values after clamping each of them to within `Band` (relative to its magnitude) of
+/// the previous combined value, so a single report can't move the published value by more than
+/// `Band` per round. The band is never narrower than `MinBand`, so a value at or near zero can
+/// still move. Without a previous value, the plain mean is returned. Returns prev_value if there
+/// is no valid value.
+pub struct CappedMeanCombineData<T, Band, MinBand, ExpiresIn>(
+	marker::PhantomData<(T, Band, MinBand, ExpiresIn)>,
+);
+
+impl<T, Band, MinBand, ExpiresIn> CombineData<OracleKeyOf<T>, TimestampedValueT>
+	for CappedMeanCombineData<T, Band, MinBand, ExpiresIn>
+where
+	T: Config,
+	T::AccountId: AsRef<[u8]> + ToHex,
+	Band: Get<Perbill>,
+	MinBand: Get<u64>,
+	ExpiresIn: Get<u128>,
+{
+	fn combine_data(
+		_key: &OracleKeyOf<T>,
+		mut values: Vec<TimestampedValueT>,
+		prev_value: Option<TimestampedValueT>,
+	) -> Option<TimestampedValueT> {
+		let expires_in = ExpiresIn::get();
+		let now = T::UnixTime::now().as_millis();
+
+		values.retain(|x| x.timestamp + expires_in > now);
+		if values.is_empty() {
+			return prev_value;
+		}
+
+		let (low, high) = match prev_value {
+			Some(prev) => {
+				let band = Band::get()
+					.mul_floor(prev.value.unsigned_abs())
+					.max(MinBand::get())
+					.min(i64::MAX as u64) as i64;
+				(prev.value.saturating_sub(band), prev.value.saturating_add(band))
+			},
+			None => (i64::MIN, i64::MAX),
+		};
+		let sum: i128 = values.iter().map(|x| x.value.clamp(low, high) as i128).sum();
+		let timestamp = values.iter().map(|x| x.timestamp).max().unwrap_or_default();
+		Some(TimestampedValue { value: (sum / values.len() as i128) as i64, timestamp })
+	}
+}
+
+impl<T, Band, MinBand, ExpiresIn> CombineKind for CappedMeanCombineData<T, Band, MinBand, ExpiresIn> {
+	fn is_averaging() -> bool {
+		true
+	}
+}
+
+/// Stake-weighted median of the operators' raw values, with each operator's stake given by
+/// `StakeOf`. Operators without stake are left out. Returns prev_value if no staked operator
+/// has a valid value.
+///
+/// The operators aren't part of the `values` handed to a combine, so they are looked up among the
+/// raw values of `key` in storage. Only raw values matching one of `values` are weighed, which
+/// keeps the caller's filtering, e.g. by source group or age. Equal reports of several operators
+/// are told apart by storage order only.
+pub struct StakeWeightedCombineData<T, StakeOf, ExpiresIn>(marker::PhantomData<(T, StakeOf, ExpiresIn)>);
+
+impl<T, StakeOf, ExpiresIn> CombineData<OracleKeyOf<T>, TimestampedValueT>
+	for StakeWeightedCombineData<T, StakeOf, ExpiresIn>
+where
+	T: Config,
+	T::AccountId: AsRef<[u8]> + ToHex,
+	StakeOf: Convert<CreatorId<T::AccountId>, u128>,
+	ExpiresIn: Get<u128>,
+{
+	fn combine_data(
+		key: &OracleKeyOf<T>,
+		mut values: Vec<TimestampedValueT>,
+		prev_value: Option<TimestampedValueT>,
+	) -> Option<TimestampedValueT> {
+		let expires_in = ExpiresIn::get();
+		let now = T::UnixTime::now().as_millis();
+
+		let mut staked: Vec<(TimestampedValueT, u128)> = Pallet::<T>::read_raw_values_by_creator(key)
+			.into_iter()
+			.filter(|(_, x)| match values.iter().position(|value| value == x) {
+				Some(index) => {
+					values.swap_remove(index);
+					true
+				},
+				None => false,
+			})
+			.filter(|(_, x)| x.timestamp + expires_in > now)
+			.map(|(cid, x)| (x, StakeOf::convert(cid)))
+			.filter(|(_, stake)| *stake > 0)
+			.collect();
+		staked.sort();
+
+		// The first value at which the running stake reaches half of the total stake.
+		let total: u128 = staked.iter().fold(0u128, |total, (_, stake)| total.saturating_add(*stake));
+		let mut running = 0u128;
+		staked
+			.into_iter()
+			.find(|(_, stake)| {
+				running = running.saturating_add(*stake);
+				running.saturating_mul(2) >= total
+			})
+			.map(|(value, _)| value)
+			.or(prev_value)
+	}
+}
+
+impl<T, StakeOf, ExpiresIn> CombineKind for StakeWeightedCombineData<T, StakeOf, ExpiresIn> {}
+
+/// Fixed-point scale of the decay weights.
+const DECAY_SCALE: u128 = 1_000_000_000_000;
+
+/// `2^(-1/2^i)` for `i` in `1..=16`, scaled by `DECAY_SCALE`.
+const DECAY_ROOTS: [u128; 16] = [
+	707_106_781_186,
+	840_896_415_253,
+	917_004_043_204,
+	957_603_280_698,
+	978_572_062_087,
+	989_228_013_193,
+	994_599_423_483,
+	997_296_056_085,
+	998_647_112_890,
+	999_323_327_502,
+	999_661_606_496,
+	999_830_788_931,
+	999_915_390_886,
+	999_957_694_548,
+	999_978_847_050,
+	999_989_423_469,
+];
+
+/// `2^(-age/half_life)` scaled by `DECAY_SCALE`, with the fractional halving resolved to 1/65536.
+fn decay_weight(age: u128, half_life: u128) -> u128 {
+	let half_life = half_life.max(1);
+	let halvings = age / half_life;
+	if halvings >= 40 {
+		return 0
+	}
+
+	let mut weight = DECAY_SCALE >> halvings;
+	let fraction = (age % half_life).saturating_mul(1 << 16) / half_life;
+	for (bit, root) in DECAY_ROOTS.iter().enumerate() {
+		if fraction & (1 << (15 - bit)) != 0 {
+			weight = weight * root / DECAY_SCALE;
+		}
+	}
+	weight
+}
+
+/// Mean of the values weighted by `2^(-age/HalfLife)`, so newer values count more.
+/// Returns prev_value if there are no valid values, and the newest value if every weight
+/// rounds down to zero.
+pub struct DecayCombineData<T, HalfLife, ExpiresIn>(marker::PhantomData<(T, HalfLife, ExpiresIn)>);
+
+impl<T, HalfLife, ExpiresIn> CombineData<OracleKeyOf<T>, TimestampedValueT>
+	for DecayCombineData<T, HalfLife, ExpiresIn>
+where
+	T: Config,
+	T::AccountId: AsRef<[u8]> + ToHex,
+	HalfLife: Get<u128>,
+	ExpiresIn: Get<u128>,
+{
+	fn combine_data(
+		_key: &OracleKeyOf<T>,
+		mut values: Vec<TimestampedValueT>,
+		prev_value: Option<TimestampedValueT>,
+	) -> Option<TimestampedValueT> {
+		let expires_in = ExpiresIn::get();
+		let now = T::UnixTime::now().as_millis();
+
+		values.retain(|x| x.timestamp + expires_in > now);
+
+		// Ties on the timestamp resolve to the larger value, whatever the order of `values`.
+		let newest = match values.iter().max_by_key(|x| (x.timestamp, x.value)) {
+			Some(newest) => *newest,
+			None => return prev_value,
+		};
+
+		let half_life = HalfLife::get();
+		let (weighted_sum, total_weight) =
+			values.iter().fold((0i128, 0u128), |(weighted_sum, total_weight), x| {
+				let weight = decay_weight(now.saturating_sub(x.timestamp), half_life);
+				(weighted_sum + weight as i128 * x.value as i128, total_weight + weight)
+			});
+		if total_weight == 0 {
+			return Some(newest)
+		}
+
+		Some(TimestampedValue {
+			value: (weighted_sum / total_weight as i128) as i64,
+			timestamp: newest.timestamp,
+		})
 	}
 }
+
+impl<T, HalfLife, ExpiresIn> CombineKind for DecayCombineData<T, HalfLife, ExpiresIn> {
+	fn is_averaging() -> bool {
+		true
+	}
+}
+
+/// Built-in combines a key can be switched to with `set_combine_strategy`, instead of
+/// `Config::CombineData`.
+///
+/// Unlike the configurable combines, they don't require a minimum number of values and never
+/// fall back to the previous value.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum CombineStrategy {
+	/// Median value, the upper one for an even number of values
+	Median,
+	/// Mean of all values
+	Mean,
+	/// Most recent value
+	Latest,
+	/// Mean of all values, each weighted by the time until the next report (or `now`)
+	Twap,
+}
+
+impl CombineStrategy {
+	/// Combine `values`, stamped with the newest timestamp unless a single value is picked.
+	pub fn combine(&self, mut values: Vec<TimestampedValueT>, now: u128) -> Option<TimestampedValueT> {
+		if values.is_empty() {
+			return None
+		}
+		// Ties on the timestamp resolve to the larger value, whatever the order of `values`.
+		values.sort_by_key(|x| (x.timestamp, x.value));
+		let newest = values[values.len() - 1];
+
+		let value = match self {
+			CombineStrategy::Median => {
+				values.sort();
+				return Some(values[values.len() / 2])
+			},
+			CombineStrategy::Latest => return Some(newest),
+			CombineStrategy::Mean => mean(&values),
+			CombineStrategy::Twap => {
+				let (weighted_sum, total_weight) =
+					values.iter().enumerate().fold((0i128, 0u128), |(weighted_sum, total_weight), (i, x)| {
+						let until = values.get(i + 1).map_or(now, |next| next.timestamp);
+						let weight = until.saturating_sub(x.timestamp);
+						(weighted_sum + weight as i128 * x.value as i128, total_weight + weight)
+					});
+				if total_weight == 0 {
+					mean(&values)
+				} else {
+					(weighted_sum / total_weight as i128) as i64
+				}
+			},
+		};
+		Some(TimestampedValue { value, timestamp: newest.timestamp })
+	}
+
+	/// Whether the combined value is derived from every value rather than picked among them.
+	pub fn is_averaging(&self) -> bool {
+		matches!(self, CombineStrategy::Mean | CombineStrategy::Twap)
+	}
+}
+
+/// Mean of non-empty `values`.
+fn mean(values: &[TimestampedValueT]) -> i64 {
+	let sum: i128 = values.iter().map(|x| x.value as i128).sum();
+	(sum / values.len() as i128) as i64
+}
@@ -15,12 +15,13 @@ use frame_support::{
     log, BoundedVec,
     pallet_prelude::*,
     traits::{Currency, EstimateCallFee, UnixTime, ChangeMembers, Get, SortedMembers},
-    IterableStorageMap, IterableStorageDoubleMap,
+    transactional, IterableStorageMap, IterableStorageDoubleMap,
 };
 use frame_system::{
     self as system,
     offchain::{
-        AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer, SubmitTransaction,
+        AppCrypto, CreateSignedTransaction, SendSignedTransaction, SendUnsignedTransaction,
+        ForAny, SignedPayload, Signer, SubmitTransaction,
     },
     pallet_prelude::*,
     Config as SystemConfig,
@@ -38,9 +39,10 @@ use sp_runtime::{
     offchain::{
         http,
         storage::{MutateStorageError, StorageRetrievalError, StorageValueRef},
-        Duration,
+        Duration, Timestamp,
     },
-    traits::{Hash, UniqueSaturatedInto, Zero},
+    traits::{Hash, IdentifyAccount, UniqueSaturatedInto, Zero},
+    PerThing, Permill,
 };
 use xcm::latest::{prelude::*, Junction, OriginKind, SendXcm, Xcm};
 use orml_traits::{CombineData, DataFeeder, DataProvider, DataProviderExtended, OnNewData};
@@ -52,7 +54,11 @@ pub use pallet::*;
 mod tests;
 
 mod default_combine_data;
-pub use default_combine_data::DefaultCombineData;
+pub mod migrations;
+pub use default_combine_data::{
+    CappedMeanCombineData, CombineKind, CombineStrategy, DecayCombineData, DefaultCombineData,
+    StakeWeightedCombineData, SupermajorityCombineData, TrimmedMeanCombineData,
+};
 
 // Runtime benchmarking features
 #[cfg(feature = "runtime-benchmarks")]
@@ -64,6 +70,14 @@ pub type BalanceOf<T> =
     <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 
 pub type OracleKeyOf<T> = BoundedVec<u8, <T as Config>::StrLimit>;
+pub type AllowlistOf<T> =
+    BoundedVec<CreatorId<<T as frame_system::Config>::AccountId>, <T as Config>::MaxAllowlistLen>;
+pub type ValuePairOf<T> = ValuePair<BoundedVec<u8, <T as Config>::MaxTagLen>>;
+pub type ApiFeedOf<T> = ApiFeed<
+    <T as frame_system::Config>::BlockNumber,
+    BoundedVec<u8, <T as Config>::MaxUrlLen>,
+    BoundedVec<u8, <T as Config>::MaxVPathLen>,
+>;
 
 /// Defines application identifier for crypto keys of this module.
 ///
@@ -103,23 +117,55 @@ pub mod crypto {
     }
 }
 
-/// Mock structure for XCM Call message encoding
-#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
-#[allow(non_camel_case_types)]
-enum KylinMockFunc {
-    #[codec(index = 7u8)]
-    xcm_feed_back { 
-        key: Vec<u8>,
-		value: i64,
-    },
+/// `xcm_feed_back` call of the destination's feed pallet, sent with query answers.
+///
+/// Encoded as the destination's `RuntimeCall`: the pallet index, the call index and then
+/// the arguments, with the indices taken from `FeedbackPalletIndex` and `FeedbackCallIndex`.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+struct FeedbackCall {
+    pallet_index: u8,
+    call_index: u8,
+    key: Vec<u8>,
+    value: i64,
+    decimals: u8,
+    /// Base and quote asset of `value`, if its feed was tagged
+    pair: Option<(Vec<u8>, Vec<u8>)>,
+    /// Operator agreement on `value`, if recorded and `value` is still the published one
+    confidence: Option<Permill>,
+}
+
+impl Encode for FeedbackCall {
+    fn size_hint(&self) -> usize {
+        2 + self.key.size_hint()
+            + self.value.size_hint()
+            + self.decimals.size_hint()
+            + self.pair.size_hint()
+            + self.confidence.size_hint()
+    }
+
+    fn encode_to<W: codec::Output + ?Sized>(&self, dest: &mut W) {
+        dest.push_byte(self.pallet_index);
+        dest.push_byte(self.call_index);
+        self.key.encode_to(dest);
+        self.value.encode_to(dest);
+        self.decimals.encode_to(dest);
+        self.pair.encode_to(dest);
+        self.confidence.encode_to(dest);
+    }
 }
 
-/// Mock structure for XCM Call message encoding
-#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
-#[allow(non_camel_case_types)]
-enum KylinMockCall {
-    #[codec(index = 168u8)]
-    KylinFeed(KylinMockFunc),
+impl Decode for FeedbackCall {
+    fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+        Ok(Self {
+            pallet_index: input.read_byte()?,
+            call_index: input.read_byte()?,
+            key: Decode::decode(input)?,
+            value: Decode::decode(input)?,
+            decimals: Decode::decode(input)?,
+            pair: Decode::decode(input)?,
+            confidence: Decode::decode(input)?,
+        })
+    }
 }
 
 // Creator may be a AccountId or from a parachain
@@ -130,20 +176,168 @@ pub enum CreatorId<AccountId> {
 }
 
 /// Feed URL Endpoint data structure
-#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, TypeInfo)]
+#[derive(Encode, Decode, Default, Clone, PartialEq, Eq, TypeInfo, MaxEncodedLen)]
 #[cfg_attr(feature = "std", derive(Debug))]
-pub struct ApiFeed<BlockNumber> {
+pub struct ApiFeed<BlockNumber, Url, VPath> {
     requested_block_number: BlockNumber,
-    url: Option<Vec<u8>>,
-    vpath: Option<Vec<u8>>,
+    url: Option<Url>,
+    vpath: Option<VPath>,
+    min: Option<i64>,
+    max: Option<i64>,
+    /// Set once the feed failed `MaxConsecutiveFailures` times in a row, the sweep skips it
+    disabled: bool,
+}
+
+/// Base and quote asset a value is denominated in, e.g. `BTC`/`USD`
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct ValuePair<Tag> {
+    pub base: Tag,
+    pub quote: Tag,
+}
+
+/// Source of a feed as exposed to auditors, see `Pallet::feed_config`
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct ApiFeedInfo {
+    pub url: Option<Vec<u8>>,
+    pub vpath: Option<Vec<u8>>,
+    pub min: Option<i64>,
+    pub max: Option<i64>,
+    /// Blocks between two fetches of the feed by one node
+    pub fetch_interval: u32,
+    /// Decimal places of the values the feed publishes, none for a boolean feed
+    pub decimals: u8,
+    /// Whether the feed was auto-disabled after repeated fetch failures
+    pub disabled: bool,
+}
+
+/// Layout version of `OracleSnapshot`, bumped whenever its encoding changes
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Most keys a single `OracleSnapshot` page covers
+pub const MAX_SNAPSHOT_KEYS: u32 = 256;
+
+/// Feeds, combined values and raw values of a page of oracle keys, see `Pallet::snapshot`
+///
+/// Entries are sorted by key, then by creator.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct OracleSnapshot<CreatorId, ApiFeed, Value> {
+    /// `SNAPSHOT_VERSION` the snapshot was taken with
+    pub version: u32,
+    pub feeds: Vec<(CreatorId, Vec<u8>, ApiFeed)>,
+    pub values: Vec<(Vec<u8>, Value)>,
+    pub raw_values: Vec<(CreatorId, Vec<u8>, Value)>,
+    /// Last key of the page if more keys follow, pass it as `from` to get the next page
+    pub next: Option<Vec<u8>>,
+}
+
+/// Decimal places of the integers the offchain worker derives from fetched floats
+pub const VALUE_DECIMALS: u8 = 6;
+
+/// XCM query answer that could not be delivered yet
+#[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo)]
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct PendingResponse {
+    para_id: ParaId,
+    key: Vec<u8>,
+    value: i64,
+    decimals: u8,
+    retries: u32,
+}
+
+/// Why the offchain worker could not produce a value for a feed
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum FetchErrorKind {
+    /// The request failed or the response was unusable
+    Http,
+    /// The response was not valid JSON
+    InvalidJson,
+    /// The value path is malformed or matches nothing
+    InvalidPath,
+    /// The value path resolved to a value of the wrong type
+    InvalidValue,
+}
+
+/// Outcome of a fed value for its key
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum FeedStatus {
+    /// The value was combined into a new published value
+    Published,
+    /// The value was stored, but the combine didn't yield a new value yet
+    PendingQuorum,
+    /// The value is outside the bounds of the key's feed and was dropped
+    OutOfBounds,
+    /// The feeder isn't on the allowlist of the key and the value was dropped
+    NotAllowlisted,
+}
+
+/// Receives every value published to the oracle's `Values`, e.g. to mirror it into a
+/// price registry.
+pub trait ValueSink<Key> {
+    fn on_value_published(key: &Key, value: i64, timestamp: u128);
+}
+
+impl<Key> ValueSink<Key> for () {
+    fn on_value_published(_key: &Key, _value: i64, _timestamp: u128) {}
+}
+
+/// Fetch health of a feed, as reported by the operators fetching it
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct FeedHealth<AccountId, BlockNumber> {
+    /// Last error and the time (in millis) it was reported
+    pub last_error: Option<(FetchErrorKind, u128)>,
+    /// Block of the last successfully fed value, not counting values skipped within
+    /// `UpdateDeadband`
+    pub last_success: Option<BlockNumber>,
+    /// Blocks with errors reported since the last success
+    pub consecutive_failures: u32,
+    /// Distinct operators that reported errors since the last success, at most one per member
+    pub reporters: Vec<AccountId>,
 }
 
-enum TransactionType {
-    Signed,
-    UnsignedForAny,
-    UnsignedForAll,
-    Raw,
-    None,
+// Not derived, `AccountId` has no default.
+impl<AccountId, BlockNumber> Default for FeedHealth<AccountId, BlockNumber> {
+    fn default() -> Self {
+        Self { last_error: None, last_success: None, consecutive_failures: 0, reporters: Vec::new() }
+    }
+}
+
+impl<AccountId, BlockNumber> FeedHealth<AccountId, BlockNumber> {
+    /// Start a new streak of failures.
+    fn reset_failures(&mut self) {
+        self.consecutive_failures = 0;
+        self.reporters.clear();
+    }
+}
+
+/// Which transactions the offchain worker submits fed values with
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub enum SubmissionMode {
+    /// Signed `feed_data` transactions, paid for by the operator keys
+    SignedOnly,
+    /// Unsigned `feed_data_unsigned` transactions carrying a payload signed by the operator key
+    UnsignedOnly,
+    /// Signed transactions, falling back to an unsigned one when none is accepted
+    Either,
+}
+
+/// Blocks for which an unsigned feed payload stays valid
+pub const UNSIGNED_FEED_LONGEVITY: u32 = 5;
+
+/// Values fed through `feed_data_unsigned`, signed with the operator's oracle key
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct FeedPayload<Public, BlockNumber, Key> {
+    pub public: Public,
+    pub block_number: BlockNumber,
+    pub values: Vec<(Key, i64)>,
+}
+
+impl<T: Config> SignedPayload<T> for FeedPayload<T::Public, T::BlockNumber, OracleKeyOf<T>>
+where
+    T::AccountId: AsRef<[u8]> + ToHex,
+{
+    fn public(&self) -> T::Public {
+        self.public.clone()
+    }
 }
 
 #[frame_support::pallet]
@@ -178,6 +372,14 @@ pub mod pallet {
 
         type XcmSender: SendXcm;
 
+        /// Index of the feed pallet in the runtime of parachains receiving query answers
+        #[pallet::constant]
+        type FeedbackPalletIndex: Get<u8>;
+
+        /// Index of `xcm_feed_back` in the calls of that feed pallet
+        #[pallet::constant]
+        type FeedbackCallIndex: Get<u8>;
+
         type UnixTime: UnixTime;
 
         /// A configuration for base priority of unsigned transactions.
@@ -196,7 +398,7 @@ pub mod pallet {
 
         /// Provide the implementation to combine raw values to produce
 		/// aggregated value
-		type CombineData: CombineData<OracleKeyOf<Self>, TimestampedValueT>;
+		type CombineData: CombineData<OracleKeyOf<Self>, TimestampedValueT> + CombineKind;
 
         /// Oracle operators.
 		type Members: SortedMembers<Self::AccountId>;
@@ -204,26 +406,167 @@ pub mod pallet {
         #[pallet::constant]
 		type StrLimit: Get<u32>;
 
-		/// Maximum size of HasDispatched
+		/// Maximum length of a feed's URL
+		#[pallet::constant]
+		type MaxUrlLen: Get<u32>;
+
+		/// Maximum length of a feed's value path
+		#[pallet::constant]
+		type MaxVPathLen: Get<u32>;
+
+		/// Maximum length of the base and quote tags of a value
+		#[pallet::constant]
+		type MaxTagLen: Get<u32>;
+
+		/// Maximum number of operators on the allowlist of a feed
+		#[pallet::constant]
+		type MaxAllowlistLen: Get<u32>;
+
+		/// Maximum number of operators and parachains feeding in a single block.
+		///
+		/// Must be at least the number of `Members` plus the parachains feeding through XCM,
+		/// feeders beyond it get `DispatchSetFull`.
 		#[pallet::constant]
 		type MaxHasDispatchedSize: Get<u32>;
 
+		/// Maximum size in bytes of a feed's HTTP response body
+		#[pallet::constant]
+		type MaxResponseBytes: Get<u32>;
+
+		/// Raw values older than this (in millis) are left out of the combine
+		#[pallet::constant]
+		type MaxRawValueAge: Get<u128>;
+
+		/// Maximum number of feeds a single creator may register
+		#[pallet::constant]
+		type MaxFeedsPerCreator: Get<u32>;
+
+		/// Maximum number of undelivered query answers kept for retry
+		#[pallet::constant]
+		type MaxPendingResponses: Get<u32>;
+
+		/// Number of retries before an undelivered query answer is abandoned
+		#[pallet::constant]
+		type MaxResponseRetries: Get<u32>;
+
+		/// Milliseconds the offchain worker may spend fetching feeds per block
+		#[pallet::constant]
+		type OffchainFetchBudget: Get<u64>;
+
+		/// Maximum number of feed HTTP requests the offchain worker has in flight at once
+		#[pallet::constant]
+		type MaxConcurrentFetches: Get<u32>;
+
+		/// Whether every local oracle key signs its own `feed_data`, or only one does.
+		///
+		/// Signing with all keys gives redundancy if one account is out of funds or not a
+		/// member, but a collator holding several member keys then feeds the same values
+		/// more than once. With a single account, the key stored in the offchain local
+		/// storage under `kylin_oracle::signing_account` is used if present, otherwise any
+		/// available key.
+		#[pallet::constant]
+		type SignWithAllAccounts: Get<bool>;
+
+		/// Which transactions the offchain worker feeds values with, and whether
+		/// `feed_data_unsigned` is accepted.
+		///
+		/// Fetch errors are always reported with signed transactions.
+		#[pallet::constant]
+		type SubmissionMode: Get<SubmissionMode>;
+
+		/// Number of recent `xcm_feed_data` nonces remembered per parachain
+		#[pallet::constant]
+		type MaxFeedNonces: Get<u32>;
+
+		/// Origin allowed to change oracle-wide settings
+		type AdminOrigin: EnsureOrigin<<Self as SystemConfig>::RuntimeOrigin>;
+
+		/// Maximum number of source groups a key falls back through
+		#[pallet::constant]
+		type MaxSourceGroups: Get<u32>;
+
+		/// Maximum number of operators in a source group
+		#[pallet::constant]
+		type MaxSourceGroupSize: Get<u32>;
+
+		/// Blocks between two fetches of the same feed by one node.
+		///
+		/// Each node fetches a feed on a block offset derived from its own random seed, so
+		/// collators running the same feeds don't all hit the source on the same block. `1`
+		/// fetches every feed on every block.
+		#[pallet::constant]
+		type FetchInterval: Get<u32>;
+
+		/// Raw values differing from the operator's previous one by less than this are not
+		/// stored, as long as the previous one is younger than half of `MaxRawValueAge`.
+		/// `0` stores every value.
+		#[pallet::constant]
+		type UpdateDeadband: Get<u64>;
+
+		/// Whether fed values outside the bounds of their key's feed are clamped into them instead
+		/// of dropped with `FeedStatus::OutOfBounds`
+		#[pallet::constant]
+		type ClampOutOfBounds: Get<bool>;
+
+		/// Only publish a combined value once every member has a fresh raw value for the key
+		#[pallet::constant]
+		type StrictAllOperators: Get<bool>;
+
+		/// Hook called with every raw value stored by `feed_data` or `xcm_feed_data`
+		type OnNewValue: OnNewData<CreatorId<Self::AccountId>, OracleKeyOf<Self>, i64>;
+
+		/// Hook called with every value written to `Values`
+		type ValueSink: ValueSink<OracleKeyOf<Self>>;
+
+		/// Consecutive combines yielding the same value after which a key is flagged as frozen.
+		/// `0` disables the check.
+		#[pallet::constant]
+		type FrozenRounds: Get<u32>;
+
+		/// Whether `get_fresh` leaves out the value of a frozen key
+		#[pallet::constant]
+		type SuppressFrozen: Get<bool>;
+
+		/// Blocks in a row with fetch failures reported after which a feed is disabled until
+		/// its creator re-enables it. `0` never disables a feed.
+		#[pallet::constant]
+		type MaxConsecutiveFailures: Get<u32>;
+
+		/// Distinct operators that must have reported a feed's consecutive failures before it
+		/// is disabled
+		#[pallet::constant]
+		type FetchFailureQuorum: Get<u32>;
+
+		/// Whether every fed value emits `FeedDispersion` with the spread of its key's raw values
+		#[pallet::constant]
+		type EmitDispersion: Get<bool>;
+
+		/// Whether published values record a confidence in `ValueConfidence`
+		#[pallet::constant]
+		type StoreConfidence: Get<bool>;
+
     }
 
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(5);
+
     #[pallet::pallet]
     #[pallet::generate_store(trait Store)]
+    #[pallet::storage_version(STORAGE_VERSION)]
     #[pallet::without_storage_info]
     pub struct Pallet<T>(_);
 
-    #[pallet::storage]
-    #[pallet::getter(fn next_unsigned_at)]
-    pub(super) type NextUnsignedAt<T: Config> = StorageValue<_, T::BlockNumber, ValueQuery>;
-
     /// Storage map for the feed URL Endpoint
     #[pallet::storage]
 	#[pallet::getter(fn api_feeds)]
 	pub type ApiFeeds<T: Config> =
-		StorageDoubleMap<_, Twox64Concat, CreatorId<T::AccountId>, Twox64Concat, OracleKeyOf<T>, ApiFeed<T::BlockNumber>>;
+		StorageDoubleMap<_, Twox64Concat, CreatorId<T::AccountId>, Twox64Concat, OracleKeyOf<T>, ApiFeedOf<T>>;
+
+	/// Feeds whose value path resolves to a JSON boolean, stored as `1`/`0`
+	#[pallet::storage]
+	#[pallet::getter(fn boolean_feeds)]
+	pub type BooleanFeeds<T: Config> =
+		StorageDoubleMap<_, Twox64Concat, CreatorId<T::AccountId>, Twox64Concat, OracleKeyOf<T>, bool, ValueQuery>;
 
     /// Raw values for each oracle operators
 	#[pallet::storage]
@@ -231,12 +574,119 @@ pub mod pallet {
 	pub type RawValues<T: Config> =
 		StorageDoubleMap<_, Twox64Concat, CreatorId<T::AccountId>, Twox64Concat, OracleKeyOf<T>, TimestampedValueT>;
 
+	/// Number of entries in `RawValues`, all of which every combine iterates.
+	#[pallet::storage]
+	#[pallet::getter(fn raw_value_count)]
+	pub type RawValueCount<T: Config> = StorageValue<_, u32, ValueQuery>;
+
 	/// Up to date combined value from Raw Values
 	#[pallet::storage]
 	#[pallet::getter(fn values)]
 	pub type Values<T: Config> =
 		StorageMap<_, Twox64Concat, OracleKeyOf<T>, TimestampedValueT>;
 
+	/// Decimal places of each combined value in `Values`
+	#[pallet::storage]
+	#[pallet::getter(fn value_decimals)]
+	pub type ValueDecimals<T: Config> =
+		StorageMap<_, Twox64Concat, OracleKeyOf<T>, u8>;
+
+	/// Creator whose feed sets the value bounds of each key, the first one to register it.
+	/// Released when that feed is removed, the next creator submitting the key takes over.
+	#[pallet::storage]
+	#[pallet::getter(fn feed_owners)]
+	pub type FeedOwners<T: Config> =
+		StorageMap<_, Twox64Concat, OracleKeyOf<T>, CreatorId<T::AccountId>>;
+
+	/// Operators allowed to feed a key, along with the creator of the feed that restricted it.
+	/// Keys without an allowlist can be fed by every member.
+	#[pallet::storage]
+	#[pallet::getter(fn feed_allowlist)]
+	pub type FeedAllowlists<T: Config> =
+		StorageMap<_, Twox64Concat, OracleKeyOf<T>, (CreatorId<T::AccountId>, AllowlistOf<T>)>;
+
+	/// Agreement of the operators on each combined value in `Values`, see `Pallet::confidence`
+	#[pallet::storage]
+	#[pallet::getter(fn value_confidence)]
+	pub type ValueConfidence<T: Config> =
+		StorageMap<_, Twox64Concat, OracleKeyOf<T>, Permill>;
+
+	/// Base and quote asset of each combined value in `Values`, if the owner of its key tagged it
+	#[pallet::storage]
+	#[pallet::getter(fn value_pairs)]
+	pub type ValuePairs<T: Config> =
+		StorageMap<_, Twox64Concat, OracleKeyOf<T>, ValuePairOf<T>>;
+
+	/// Query answers whose XCM send failed, retried in `on_initialize`
+	#[pallet::storage]
+	#[pallet::getter(fn pending_responses)]
+	pub type PendingResponses<T: Config> =
+		StorageValue<_, BoundedVec<PendingResponse, T::MaxPendingResponses>, ValueQuery>;
+
+	/// Fetch health of every feed, by its creator and key
+	#[pallet::storage]
+	#[pallet::getter(fn feed_health)]
+	pub type FeedHealths<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		CreatorId<T::AccountId>,
+		Twox64Concat,
+		OracleKeyOf<T>,
+		FeedHealth<T::AccountId, T::BlockNumber>,
+		ValueQuery,
+	>;
+
+	/// Built-in combine used for a key instead of `Config::CombineData`
+	#[pallet::storage]
+	#[pallet::getter(fn combine_strategies)]
+	pub type CombineStrategies<T: Config> =
+		StorageMap<_, Twox64Concat, OracleKeyOf<T>, CombineStrategy, OptionQuery>;
+
+	/// Operator groups a key is combined from, tried in order until one yields a value
+	#[pallet::storage]
+	#[pallet::getter(fn source_groups)]
+	pub type SourceGroups<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		OracleKeyOf<T>,
+		BoundedVec<BoundedVec<CreatorId<T::AccountId>, T::MaxSourceGroupSize>, T::MaxSourceGroups>,
+		ValueQuery,
+	>;
+
+	/// Block number of the last `feed_data_unsigned` payload accepted from each operator
+	#[pallet::storage]
+	#[pallet::getter(fn last_unsigned_feed)]
+	pub type LastUnsignedFeed<T: Config> =
+		StorageMap<_, Twox64Concat, T::AccountId, T::BlockNumber, OptionQuery>;
+
+	/// Recent nonces of `xcm_feed_data` per parachain, oldest first
+	#[pallet::storage]
+	#[pallet::getter(fn feed_nonces)]
+	pub type FeedNonces<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, BoundedVec<u64, T::MaxFeedNonces>, ValueQuery>;
+
+	#[pallet::type_value]
+	pub fn DefaultFetchingEnabled() -> bool {
+		true
+	}
+
+	/// Whether offchain workers fetch the API feeds
+	#[pallet::storage]
+	#[pallet::getter(fn fetching_enabled)]
+	pub type FetchingEnabled<T: Config> = StorageValue<_, bool, ValueQuery, DefaultFetchingEnabled>;
+
+	/// Keys whose value is pinned by `force_feed_value`, the combine is skipped for them
+	#[pallet::storage]
+	#[pallet::getter(fn forced_values)]
+	#[allow(clippy::disallowed_types)] // Allow `frame_support::pallet_prelude::ValueQuery` because default of false is correct
+	pub type ForcedValues<T: Config> = StorageMap<_, Twox64Concat, OracleKeyOf<T>, bool, ValueQuery>;
+
+	/// Number of consecutive combines that left the value of a key unchanged
+	#[pallet::storage]
+	#[pallet::getter(fn unchanged_rounds)]
+	#[allow(clippy::disallowed_types)] // Allow `frame_support::pallet_prelude::ValueQuery` because default of 0 is correct
+	pub type UnchangedRounds<T: Config> = StorageMap<_, Twox64Concat, OracleKeyOf<T>, u32, ValueQuery>;
+
 	/// If an oracle operator has fed a value in this block
 	#[pallet::storage]
 	pub(crate) type HasDispatched<T: Config> =
@@ -250,8 +700,30 @@ pub mod pallet {
 		NoPermission,
 		/// Feeder has already feeded at this block
 		AlreadyFeeded,
+		/// `MaxHasDispatchedSize` feeders have already feeded at this block
+		DispatchSetFull,
         /// XCM Send error
         XcmSendError,
+        /// The creator already has a feed registered under this key
+        FeedAlreadyExists,
+        /// The creator has reached MaxFeedsPerCreator
+        TooManyFeeds,
+        /// The same key is submitted more than once
+        DuplicateFeedKey,
+        /// XCM feed with this nonce has already been applied
+        DuplicateSubmission,
+        /// Too many source groups, or too many operators in one
+        TooManySourceGroups,
+        /// The minimum of a feed is above its maximum
+        InvalidValueBounds,
+        /// The feed is not disabled
+        FeedNotDisabled,
+        /// No combined value is published for the queried key
+        NoValueForKey,
+        /// The allowlist is longer than `MaxAllowlistLen`
+        TooManyAllowedOperators,
+        /// The unsigned payload is no newer than the last one accepted from the operator
+        StalePayload,
     }
 
     #[pallet::hooks]
@@ -261,7 +733,9 @@ pub mod pallet {
     {
         /// `on_initialize` to return the weight used in `on_finalize`.
 		fn on_initialize(_n: T::BlockNumber) -> Weight {
+			let retried = Self::retry_pending_responses();
 			T::WeightInfo::on_finalize()
+				.saturating_add(T::WeightInfo::retry_pending_responses(retried))
 		}
 
 		fn on_finalize(_n: T::BlockNumber) {
@@ -269,12 +743,32 @@ pub mod pallet {
 			<HasDispatched<T>>::kill();
 		}
 
+		fn on_runtime_upgrade() -> Weight {
+			migrations::v1::migrate::<T>()
+				.saturating_add(migrations::v2::migrate::<T>())
+				.saturating_add(migrations::v3::migrate::<T>())
+				.saturating_add(migrations::v4::migrate::<T>())
+				.saturating_add(migrations::v5::migrate::<T>())
+		}
+
+		fn integrity_test() {
+			assert!(
+				T::MaxHasDispatchedSize::get() as usize >= T::Members::count(),
+				"`MaxHasDispatchedSize` must fit every oracle member"
+			);
+		}
+
         fn offchain_worker(block_number: T::BlockNumber) {
             // Note that having logs compiled to WASM may cause the size of the blob to increase
             // significantly. You can use `RuntimeDebug` custom derive to hide details of the types
             // in WASM. The `sp-api` crate also provides a feature `disable-logging` to disable
             // all logging and thus, remove any logging from the WASM.
 
+            // There is no parent block to speak of, nor any feed to fetch, at genesis.
+            if block_number.is_zero() {
+                return;
+            }
+
             let parent_hash = <system::Pallet<T>>::block_hash(block_number - 1u32.into());
             log::debug!(
                 "Current block: {:?} (parent hash: {:?})",
@@ -282,6 +776,11 @@ pub mod pallet {
                 parent_hash
             );
 
+            if !Self::fetching_enabled() {
+                log::debug!("Feed fetching is disabled, skipping");
+                return;
+            }
+
             // It's a good practice to keep `fn offchain_worker()` function minimal, and move most
             // of the code to separate `impl` block.
             // Here we call a helper function to calculate current average price.
@@ -311,38 +810,101 @@ pub mod pallet {
 		/// 
 		/// # Emits
 		/// * `NewFeedData`
-		#[pallet::weight(T::WeightInfo::feed_data(values.len() as u32))]
+		#[pallet::weight(T::WeightInfo::feed_data(
+			values.len() as u32,
+			Self::raw_value_count().saturating_add(values.len() as u32),
+		))]
 		pub fn feed_data(
 			origin: OriginFor<T>,
 			values: Vec<(OracleKeyOf<T>, i64)>,
 		) -> DispatchResultWithPostInfo {
 			let feeder = ensure_signed(origin)?;
-            let cid = CreatorId::AccountId(feeder.clone());
-            // ensure feeder is authorized
-            ensure!(T::Members::contains(&feeder), Error::<T>::NoPermission);
 
-            // ensure account hasn't dispatched an updated yet
+            Self::do_feed_data(feeder, values)
+		}
+
+        /// Feed the values of a payload signed by an oracle operator key.
+		///
+		/// Call by the offchain worker, as an unsigned transaction, unless `SubmissionMode` is
+		/// `SignedOnly`.
+		///
+		/// # Parameter:
+		/// * `payload` - fed values and the operator key that signed them
+		/// * `signature` - signature of `payload`, checked in `validate_unsigned`
+		///
+		/// A payload is only accepted if it is newer than the last one of the operator.
+		/// 
+		/// # Emits
+		/// * `NewFeedData`
+		#[pallet::weight(T::WeightInfo::feed_data(
+			payload.values.len() as u32,
+			Self::raw_value_count().saturating_add(payload.values.len() as u32),
+		))]
+		pub fn feed_data_unsigned(
+			origin: OriginFor<T>,
+			payload: FeedPayload<T::Public, T::BlockNumber, OracleKeyOf<T>>,
+			signature: T::Signature,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+            ensure!(T::SubmissionMode::get() != SubmissionMode::SignedOnly, Error::<T>::NoPermission);
+
+            let feeder = payload.public.into_account();
             ensure!(
-                HasDispatched::<T>::mutate(|set| set.insert(cid.clone())),
-                Error::<T>::AlreadyFeeded
+                Self::last_unsigned_feed(&feeder).map_or(true, |last| payload.block_number > last),
+                Error::<T>::StalePayload
             );
+            <LastUnsignedFeed<T>>::insert(&feeder, payload.block_number);
 
-            let now = T::UnixTime::now().as_millis();
-            for (key, value) in &values {
-                let timestamped = TimestampedValue {
-                    value: value.clone(),
-                    timestamp: now,
-                };
-                RawValues::<T>::insert(&cid, &key, timestamped);
+            Self::do_feed_data(feeder, payload.values)
+		}
 
-                // Update `Values` storage if `combined` yielded result.
-                if let Some(combined) = Self::combined(key) {
-                    <Values<T>>::insert(key, combined);
+        /// Report feeds the offchain worker failed to fetch.
+		///
+		/// Call by the offchain worker.
+		///
+		/// A feed is disabled once it failed in `MaxConsecutiveFailures` blocks in a row, as
+		/// reported by at least `FetchFailureQuorum` distinct operators. A feed's failures count
+		/// once per block, and failures of unknown feeds are ignored.
+		///
+		/// # Parameter:
+		/// * `errors` - creators and keys of the failed feeds, and why they failed
+		/// 
+		/// # Emits
+		/// * `FeedAutoDisabled` for each feed that got disabled
+		#[pallet::weight(T::WeightInfo::report_fetch_errors(errors.len() as u32))]
+		pub fn report_fetch_errors(
+			origin: OriginFor<T>,
+			errors: Vec<(CreatorId<T::AccountId>, OracleKeyOf<T>, FetchErrorKind)>,
+		) -> DispatchResultWithPostInfo {
+			let feeder = ensure_signed(origin)?;
+            // ensure feeder is authorized
+            ensure!(T::Members::contains(&feeder), Error::<T>::NoPermission);
+
+            let now = T::UnixTime::now().as_millis();
+            let max_failures = T::MaxConsecutiveFailures::get();
+            for (creator, key, kind) in errors {
+                if !ApiFeeds::<T>::contains_key(&creator, &key) {
+                    continue;
+                }
+                let (failures, reporters) = FeedHealths::<T>::mutate(&creator, &key, |health| {
+                    // The timestamp only moves between blocks, a repeated report isn't counted.
+                    if health.last_error.as_ref().map_or(true, |(_, at)| *at != now) {
+                        health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+                    }
+                    if !health.reporters.contains(&feeder) {
+                        health.reporters.push(feeder.clone());
+                    }
+                    health.last_error = Some((kind, now));
+                    (health.consecutive_failures, health.reporters.len() as u32)
+                });
+                if max_failures > 0
+                    && failures >= max_failures
+                    && reporters >= T::FetchFailureQuorum::get()
+                {
+                    Self::auto_disable_feed(creator, key, failures);
                 }
             }
-
-            Self::deposit_event(Event::NewFeedData { sender: cid, values });
-			Ok(Pays::No.into())
+			Ok(().into())
 		}
 
         /// Feed the external value.
@@ -351,13 +913,18 @@ pub mod pallet {
 		///
 		/// # Parameter:
 		/// * `values` - value array for the feed
+		/// * `nonce` - optional message nonce, a replayed nonce is rejected
 		/// 
 		/// # Emits
 		/// * `NewFeedData`
-        #[pallet::weight(T::WeightInfo::feed_data(values.len() as u32))]
+        #[pallet::weight(T::WeightInfo::feed_data(
+            values.len() as u32,
+            Self::raw_value_count().saturating_add(values.len() as u32),
+        ))]
 		pub fn xcm_feed_data(
 			origin: OriginFor<T>,
 			values: Vec<(OracleKeyOf<T>, i64)>,
+			nonce: Option<u64>,
 		) -> DispatchResultWithPostInfo {
             let para_id =
                 ensure_sibling_para(<T as Config>::RuntimeOrigin::from(origin))?;
@@ -366,27 +933,65 @@ pub mod pallet {
             // // ensure feeder is authorized
             // ensure!(T::Members::contains(&feeder), Error::<T>::NoPermission);
 
+            // ensure a redelivered message isn't applied twice
+            if let Some(nonce) = nonce {
+                FeedNonces::<T>::try_mutate(para_id, |seen| -> DispatchResult {
+                    ensure!(!seen.contains(&nonce), Error::<T>::DuplicateSubmission);
+                    if !seen.is_empty() && seen.len() as u32 >= T::MaxFeedNonces::get() {
+                        seen.remove(0);
+                    }
+                    // Only fails if no nonces are remembered at all.
+                    let _ = seen.try_push(nonce);
+                    Ok(())
+                })?;
+            }
+
             // ensure account hasn't dispatched an updated yet
-            ensure!(
-                HasDispatched::<T>::mutate(|set| set.insert(cid.clone())),
-                Error::<T>::AlreadyFeeded
-            );
+            Self::mark_dispatched(&cid)?;
 
             let now = T::UnixTime::now().as_millis();
-            for (key, value) in &values {
+            let mut applied = Vec::with_capacity(values.len());
+            for (key, value) in values {
+                if !Self::is_allowlisted(&cid, &key) {
+                    applied.push((key, value, FeedStatus::NotAllowlisted));
+                    continue;
+                }
+                let value = match Self::bound_value(&key, value) {
+                    Some(value) => value,
+                    None => {
+                        applied.push((key, value, FeedStatus::OutOfBounds));
+                        continue;
+                    },
+                };
                 let timestamped = TimestampedValue {
-                    value: value.clone(),
+                    value,
                     timestamp: now,
                 };
-                RawValues::<T>::insert(&cid, &key, timestamped);
+                Self::insert_raw_value(&cid, &key, timestamped);
+                T::OnNewValue::on_new_data(&cid, &key, &value);
+                Self::note_dispersion(&key);
 
                 // Update `Values` storage if `combined` yielded result.
-                if let Some(combined) = Self::combined(key) {
-                    <Values<T>>::insert(key, combined);
-                }
+                let status = match Self::combined_with_provenance(&key) {
+                    Some((combined, contributors, spread)) => {
+                        Self::note_combined_round(&key, &combined);
+                        <Values<T>>::insert(&key, combined);
+                        <ValueDecimals<T>>::insert(&key, Self::decimals_of(&key));
+                        Self::note_confidence(&key, &combined, spread);
+                        T::ValueSink::on_value_published(&key, combined.value, combined.timestamp);
+                        Self::deposit_event(Event::ValueCombined {
+                            key: key.clone(),
+                            value: combined,
+                            contributors,
+                        });
+                        FeedStatus::Published
+                    },
+                    None => FeedStatus::PendingQuorum,
+                };
+                applied.push((key, value, status));
             }
 
-            Self::deposit_event(Event::NewFeedData { sender: cid, values });
+            Self::deposit_event(Event::NewFeedData { sender: cid, values: applied });
 			Ok(Pays::No.into())
 		}
         
@@ -405,10 +1010,18 @@ pub mod pallet {
 			let para_id =
                 ensure_sibling_para(<T as Config>::RuntimeOrigin::from(origin))?;
 
-            if let Some(val) = Self::get(&key) {
-                Self::send_qret_to_parachain(para_id, key.into(), val.value.into())
+            if let Some((val, decimals)) = Self::get_with_decimals(&key) {
+                let key: Vec<u8> = key.into();
+                if Self::send_qret_to_parachain(para_id, key.clone(), val.value, decimals).is_err() {
+                    // Keep the answer around so a temporary channel outage doesn't lose it.
+                    PendingResponses::<T>::try_mutate(|pending| {
+                        pending.try_push(PendingResponse { para_id, key, value: val.value, decimals, retries: 0 })
+                    })
+                    .map_err(|_| Error::<T>::XcmSendError)?;
+                }
+                Ok(())
             } else {
-                Err(DispatchError::CannotLookup)
+                Err(Error::<T>::NoValueForKey.into())
             }
             
 		}
@@ -423,15 +1036,25 @@ pub mod pallet {
         /// * `vpath` - value path of the URL result
 		///     example: json = {"x":{"y": ["z", "zz"]}}
         ///     path: "/x/y/1" = "zz" 
+		/// * `min` - lowest accepted value, optional
+		/// * `max` - highest accepted value, optional
+		/// * `pair` - base and quote asset the value is denominated in, optional, only the owner
+		///     of the key may set it
+		/// * `allowlist` - operators allowed to feed the key, optional, all members if unset
 		/// 
 		/// # Emits
 		/// * `NewApiFeed`
-        #[pallet::weight(T::WeightInfo::submit_api())]
+        #[pallet::weight(T::WeightInfo::submit_api(T::MaxFeedsPerCreator::get()))]
+        #[allow(clippy::too_many_arguments)]
         pub fn submit_api(
             origin: OriginFor<T>,
             key: OracleKeyOf<T>,
             url: Vec<u8>,
             vpath: Vec<u8>,
+            min: Option<i64>,
+            max: Option<i64>,
+            pair: Option<(Vec<u8>, Vec<u8>)>,
+            allowlist: Option<Vec<CreatorId<T::AccountId>>>,
         ) -> DispatchResult {
             let submitter = ensure_signed(origin)?;
             let cid = CreatorId::AccountId(submitter.clone());
@@ -439,10 +1062,42 @@ pub mod pallet {
             // ensure submitter is authorized
             ensure!(T::Members::contains(&submitter), Error::<T>::NoPermission);
             
-            Self::do_submit_api(cid, key, url, vpath)?;
+            Self::do_submit_api(cid, key, url, vpath, min, max, pair, allowlist)?;
 			Ok(())
         }
 
+        /// Submit the URL Endpoints for several feeds at once.
+		///
+		/// Can be called by authorized origin. Either all feeds are registered or none.
+		///
+		/// # Parameter:
+		/// * `feeds` - `(key, url, vpath)` for each feed, see `submit_api`
+		/// 
+		/// # Emits
+		/// * `NewApiFeed` for each feed
+        #[pallet::weight(T::WeightInfo::submit_api_batch(feeds.len() as u32, T::MaxFeedsPerCreator::get()))]
+        #[transactional]
+        pub fn submit_api_batch(
+            origin: OriginFor<T>,
+            feeds: Vec<(OracleKeyOf<T>, Vec<u8>, Vec<u8>)>,
+        ) -> DispatchResult {
+            let submitter = ensure_signed(origin)?;
+            let cid = CreatorId::AccountId(submitter.clone());
+
+            // ensure submitter is authorized
+            ensure!(T::Members::contains(&submitter), Error::<T>::NoPermission);
+
+            let mut keys: Vec<&OracleKeyOf<T>> = feeds.iter().map(|(key, _, _)| key).collect();
+            keys.sort();
+            keys.dedup();
+            ensure!(keys.len() == feeds.len(), Error::<T>::DuplicateFeedKey);
+
+            for (key, url, vpath) in feeds {
+                Self::do_submit_api(cid.clone(), key, url, vpath, None, None, None, None)?;
+            }
+            Ok(())
+        }
+
         /// Remove the URL Endpoint for the feed.
 		///
 		/// Can be called by authorized origin.
@@ -467,53 +1122,322 @@ pub mod pallet {
             Ok(())
         }
 
-        /// Submit the URL Endpoint for the feed.
+        /// Transfer the URL Endpoint for the feed to another account.
 		///
-		/// Can be only XCM call from feed parachain.
+		/// Can be called by the creator of the feed.
 		///
 		/// # Parameter:
 		/// * `key` - key for the feed
-		/// * `url` - url for the feed
-		/// * `vpath` - value path of the URL result
-		///     example: json = {"x":{"y": ["z", "zz"]}}
-        ///     path: "/x/y/1" = "zz"
-        ///  
+		/// * `new_creator` - account that will own the feed
+		/// 
 		/// # Emits
-		/// * `NewApiFeed`
-        #[pallet::weight(T::WeightInfo::submit_api())]
-        pub fn xcm_submit_api(
+		/// * `FeedOwnershipTransferred`
+        #[pallet::weight(T::WeightInfo::transfer_feed())]
+        pub fn transfer_feed(
             origin: OriginFor<T>,
             key: OracleKeyOf<T>,
-            url: Vec<u8>,
-            vpath: Vec<u8>,
+            new_creator: T::AccountId,
         ) -> DispatchResult {
-            let para_id =
-                ensure_sibling_para(<T as Config>::RuntimeOrigin::from(origin))?;
-            let cid = CreatorId::ParaId(para_id);
+            let submitter = ensure_signed(origin)?;
+            let cid = CreatorId::AccountId(submitter.clone());
 
             // ensure submitter is authorized
-            //ensure!(T::Members::contains(&submitter), Error::<T>::NoPermission);
-            
-            Self::do_submit_api(cid, key, url, vpath)?;
-			Ok(())
+            ensure!(T::Members::contains(&submitter), Error::<T>::NoPermission);
+
+            Self::do_transfer_feed(cid, key, CreatorId::AccountId(new_creator))
         }
 
-        /// Remove the URL Endpoint for the feed.
+        /// Remove every feed of the caller, along with its raw values and fetch health.
 		///
-		/// Can be only XCM call from feed parachain.
+		/// The oracle holds no operator stake, so there's nothing to refund. Feeds are bounded
+		/// by `MaxFeedsPerCreator`; at most `limit` raw values are cleared, call again to clear
+		/// the rest.
+		///
+		/// # Parameter:
+		/// * `limit` - most raw values to clear
+		/// 
+		/// # Emits
+		/// * `OperatorDeregistered`
+        #[pallet::weight(T::WeightInfo::deregister_operator(T::MaxFeedsPerCreator::get(), *limit))]
+        pub fn deregister_operator(
+            origin: OriginFor<T>,
+            limit: u32,
+        ) -> DispatchResultWithPostInfo {
+            let operator = ensure_signed(origin)?;
+            let cid = CreatorId::AccountId(operator);
+
+            let mut feeds_removed = 0u32;
+            for (key, _) in <ApiFeeds<T>>::drain_prefix(&cid).take(T::MaxFeedsPerCreator::get() as usize) {
+                <BooleanFeeds<T>>::remove(&cid, &key);
+                <FeedHealths<T>>::remove(&cid, &key);
+                Self::remove_allowlist_of(&cid, &key);
+                Self::release_feed_owner(&cid, &key);
+                feeds_removed += 1;
+            }
+            let raw_values_removed = <RawValues<T>>::clear_prefix(&cid, limit, None).unique;
+            RawValueCount::<T>::mutate(|count| *count = count.saturating_sub(raw_values_removed));
+
+            Self::deposit_event(Event::OperatorDeregistered {
+                operator: cid,
+                feeds_removed,
+                raw_values_removed,
+            });
+            Ok(Some(T::WeightInfo::deregister_operator(feeds_removed, raw_values_removed)).into())
+        }
+
+        /// Flag whether the feed resolves to a JSON boolean rather than a number.
+		///
+		/// Can be called by the creator of the feed.
 		///
 		/// # Parameter:
 		/// * `key` - key for the feed
+		/// * `boolean` - store `true`/`false` as `1`/`0` instead of rejecting them
 		/// 
 		/// # Emits
-		/// * `ApiFeedRemoved`
-        #[pallet::weight(T::WeightInfo::remove_api())]
-        pub fn xcm_remove_api(
+		/// * `BooleanFeedSet`
+        #[pallet::weight(T::WeightInfo::set_feed_boolean())]
+        pub fn set_feed_boolean(
             origin: OriginFor<T>,
             key: OracleKeyOf<T>,
+            boolean: bool,
         ) -> DispatchResult {
-            let para_id =
-                ensure_sibling_para(<T as Config>::RuntimeOrigin::from(origin))?;
+            let submitter = ensure_signed(origin)?;
+            let cid = CreatorId::AccountId(submitter.clone());
+
+            // ensure submitter is authorized
+            ensure!(T::Members::contains(&submitter), Error::<T>::NoPermission);
+            ensure!(ApiFeeds::<T>::contains_key(&cid, &key), DispatchError::CannotLookup);
+
+            if boolean {
+                <BooleanFeeds<T>>::insert(&cid, &key, true);
+            } else {
+                <BooleanFeeds<T>>::remove(&cid, &key);
+            }
+            Self::deposit_event(Event::BooleanFeedSet { sender: cid, key, boolean });
+            Ok(())
+        }
+
+        /// Re-enable a feed that was disabled after repeated fetch failures.
+		///
+		/// The consecutive failures reported for the feed are reset, so it gets
+		/// `MaxConsecutiveFailures` new attempts.
+		///
+		/// Can be called by the creator of the feed.
+		///
+		/// # Parameter:
+		/// * `key` - key for the feed
+		/// 
+		/// # Emits
+		/// * `FeedEnabled`
+        #[pallet::weight(T::WeightInfo::enable_feed())]
+        pub fn enable_feed(
+            origin: OriginFor<T>,
+            key: OracleKeyOf<T>,
+        ) -> DispatchResult {
+            let submitter = ensure_signed(origin)?;
+            let cid = CreatorId::AccountId(submitter.clone());
+
+            // ensure submitter is authorized
+            ensure!(T::Members::contains(&submitter), Error::<T>::NoPermission);
+
+            ApiFeeds::<T>::try_mutate(&cid, &key, |feed| -> DispatchResult {
+                let feed = feed.as_mut().ok_or(DispatchError::CannotLookup)?;
+                ensure!(feed.disabled, Error::<T>::FeedNotDisabled);
+                feed.disabled = false;
+                Ok(())
+            })?;
+            FeedHealths::<T>::mutate_exists(&cid, &key, |health| {
+                if let Some(health) = health {
+                    health.reset_failures();
+                }
+            });
+            Self::deposit_event(Event::FeedEnabled { sender: cid, key });
+            Ok(())
+        }
+
+        /// Set the ordered operator groups the key is combined from.
+		///
+		/// The first group whose values combine into a result is published, later groups
+		/// are only used when the earlier ones lack quorum or fresh values.
+		///
+		/// Can be called by `AdminOrigin`.
+		///
+		/// # Parameter:
+		/// * `key` - key for the feed
+		/// * `groups` - operator groups by priority, empty to combine from all operators
+		/// 
+		/// # Emits
+		/// * `SourceGroupsSet`
+        #[pallet::weight(T::WeightInfo::set_source_groups(
+            groups.len() as u32,
+            groups.iter().fold(0u32, |sources, group| sources.saturating_add(group.len() as u32)),
+        ))]
+        pub fn set_source_groups(
+            origin: OriginFor<T>,
+            key: OracleKeyOf<T>,
+            groups: Vec<Vec<CreatorId<T::AccountId>>>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let count = groups.len() as u32;
+            let groups: Vec<BoundedVec<_, T::MaxSourceGroupSize>> = groups
+                .into_iter()
+                .map(|group| group.try_into().map_err(|_| Error::<T>::TooManySourceGroups))
+                .collect::<Result<_, _>>()?;
+            let groups: BoundedVec<_, T::MaxSourceGroups> =
+                groups.try_into().map_err(|_| Error::<T>::TooManySourceGroups)?;
+            if groups.is_empty() {
+                <SourceGroups<T>>::remove(&key);
+            } else {
+                <SourceGroups<T>>::insert(&key, groups);
+            }
+            Self::deposit_event(Event::SourceGroupsSet { key, groups: count });
+            Ok(())
+        }
+
+        /// Set the built-in combine a key is combined with.
+		///
+		/// Can be called by `AdminOrigin`.
+		///
+		/// # Parameter:
+		/// * `key` - key for the feed
+		/// * `strategy` - built-in combine, `None` to use `Config::CombineData`
+		/// 
+		/// # Emits
+		/// * `CombineStrategySet`
+        #[pallet::weight(T::WeightInfo::set_combine_strategy())]
+        pub fn set_combine_strategy(
+            origin: OriginFor<T>,
+            key: OracleKeyOf<T>,
+            strategy: Option<CombineStrategy>,
+        ) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            match strategy {
+                Some(strategy) => <CombineStrategies<T>>::insert(&key, strategy),
+                None => <CombineStrategies<T>>::remove(&key),
+            }
+            Self::deposit_event(Event::CombineStrategySet { key, strategy });
+            Ok(())
+        }
+
+        /// Enable or disable the fetching of API feeds by the offchain workers.
+		///
+		/// Feeds are kept while fetching is disabled.
+		///
+		/// Can be called by `AdminOrigin`.
+		///
+		/// # Parameter:
+		/// * `enabled` - whether offchain workers fetch the feeds
+		/// 
+		/// # Emits
+		/// * `FetchingEnabledSet`
+        #[pallet::weight(T::WeightInfo::set_fetching_enabled())]
+        pub fn set_fetching_enabled(origin: OriginFor<T>, enabled: bool) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            <FetchingEnabled<T>>::put(enabled);
+            Self::deposit_event(Event::FetchingEnabledSet { enabled });
+            Ok(())
+        }
+
+        /// Pin the combined value of a key, e.g. when none of its sources can be trusted.
+		///
+		/// Fed values are still stored, but aren't combined until `clear_forced_value`.
+		///
+		/// Can be called by `AdminOrigin`.
+		///
+		/// # Parameter:
+		/// * `key` - key for the feed
+		/// * `value` - value to publish
+		/// 
+		/// # Emits
+		/// * `ValueForced`
+        #[pallet::weight(T::WeightInfo::force_feed_value())]
+        pub fn force_feed_value(origin: OriginFor<T>, key: OracleKeyOf<T>, value: i64) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+
+            let timestamped = TimestampedValue {
+                value,
+                timestamp: T::UnixTime::now().as_millis(),
+            };
+            <Values<T>>::insert(&key, timestamped);
+            <ValueDecimals<T>>::insert(&key, Self::decimals_of(&key));
+            <ValueConfidence<T>>::remove(&key);
+            T::ValueSink::on_value_published(&key, value, timestamped.timestamp);
+            <ForcedValues<T>>::insert(&key, true);
+            Self::deposit_event(Event::ValueForced { key, value: timestamped });
+            Ok(())
+        }
+
+        /// Let fed values be combined again for a key pinned by `force_feed_value`.
+		///
+		/// The forced value stays published until the next combine yields a value.
+		///
+		/// Can be called by `AdminOrigin`.
+		///
+		/// # Parameter:
+		/// * `key` - key for the feed
+		/// 
+		/// # Emits
+		/// * `ForcedValueCleared`
+        #[pallet::weight(T::WeightInfo::clear_forced_value())]
+        pub fn clear_forced_value(origin: OriginFor<T>, key: OracleKeyOf<T>) -> DispatchResult {
+            T::AdminOrigin::ensure_origin(origin)?;
+            ensure!(Self::forced_values(&key), DispatchError::CannotLookup);
+
+            <ForcedValues<T>>::remove(&key);
+            Self::deposit_event(Event::ForcedValueCleared { key });
+            Ok(())
+        }
+
+        /// Submit the URL Endpoint for the feed.
+		///
+		/// Can be only XCM call from feed parachain.
+		///
+		/// # Parameter:
+		/// * `key` - key for the feed
+		/// * `url` - url for the feed
+		/// * `vpath` - value path of the URL result
+		///     example: json = {"x":{"y": ["z", "zz"]}}
+        ///     path: "/x/y/1" = "zz"
+        ///  
+		/// # Emits
+		/// * `NewApiFeed`
+        #[pallet::weight(T::WeightInfo::submit_api(T::MaxFeedsPerCreator::get()))]
+        pub fn xcm_submit_api(
+            origin: OriginFor<T>,
+            key: OracleKeyOf<T>,
+            url: Vec<u8>,
+            vpath: Vec<u8>,
+        ) -> DispatchResult {
+            let para_id =
+                ensure_sibling_para(<T as Config>::RuntimeOrigin::from(origin))?;
+            let cid = CreatorId::ParaId(para_id);
+
+            // ensure submitter is authorized
+            //ensure!(T::Members::contains(&submitter), Error::<T>::NoPermission);
+            
+            Self::do_submit_api(cid, key, url, vpath, None, None, None, None)?;
+			Ok(())
+        }
+
+        /// Remove the URL Endpoint for the feed.
+		///
+		/// Can be only XCM call from feed parachain.
+		///
+		/// # Parameter:
+		/// * `key` - key for the feed
+		/// 
+		/// # Emits
+		/// * `ApiFeedRemoved`
+        #[pallet::weight(T::WeightInfo::remove_api())]
+        pub fn xcm_remove_api(
+            origin: OriginFor<T>,
+            key: OracleKeyOf<T>,
+        ) -> DispatchResult {
+            let para_id =
+                ensure_sibling_para(<T as Config>::RuntimeOrigin::from(origin))?;
             let cid = CreatorId::ParaId(para_id);
 
             // ensure submitter is authorized
@@ -540,10 +1464,10 @@ pub mod pallet {
             SendError,
             ParaId,
         ),
-        /// New feed data is submitted.
+        /// New feed data is submitted, along with whether each value got published.
 		NewFeedData {
 			sender: CreatorId<T::AccountId>,
-			values: Vec<(OracleKeyOf<T>, i64)>,
+			values: Vec<(OracleKeyOf<T>, i64, FeedStatus)>,
 		},
         NewParaEvt {
             para_id: ParaId,
@@ -555,13 +1479,90 @@ pub mod pallet {
 		NewApiFeed {
 			sender: CreatorId<T::AccountId>,
             key: OracleKeyOf<T>,
-            feed: ApiFeed<T::BlockNumber>,
+            feed: ApiFeedOf<T>,
 		},
         /// Apifeed is removed.
 		ApiFeedRemoved {
 			sender: CreatorId<T::AccountId>,
             key: OracleKeyOf<T>,
-            feed: ApiFeed<T::BlockNumber>,
+            feed: ApiFeedOf<T>,
+		},
+        /// Combined value is updated, `contributors` reported the published value.
+		ValueCombined {
+            key: OracleKeyOf<T>,
+            value: TimestampedValueT,
+            contributors: Vec<CreatorId<T::AccountId>>,
+		},
+        /// Query answer couldn't be delivered after `MaxResponseRetries` retries.
+		QueryAnswerAbandoned {
+			para_id: ParaId,
+            key: Vec<u8>,
+		},
+        /// Apifeed is moved to another creator.
+		FeedOwnershipTransferred {
+			from: CreatorId<T::AccountId>,
+			to: CreatorId<T::AccountId>,
+            key: OracleKeyOf<T>,
+		},
+        /// Feed is flagged, or no longer flagged, as a boolean feed.
+		BooleanFeedSet {
+			sender: CreatorId<T::AccountId>,
+            key: OracleKeyOf<T>,
+            boolean: bool,
+		},
+        /// Source groups of a key are replaced, no groups means all operators.
+		SourceGroupsSet {
+            key: OracleKeyOf<T>,
+            groups: u32,
+		},
+        /// The built-in combine of a key was set, or cleared if `None`.
+		CombineStrategySet {
+            key: OracleKeyOf<T>,
+            strategy: Option<CombineStrategy>,
+		},
+        /// Offchain fetching of the feeds is enabled or disabled.
+		FetchingEnabledSet {
+            enabled: bool,
+		},
+        /// The value of a key is pinned by governance.
+		ValueForced {
+            key: OracleKeyOf<T>,
+            value: TimestampedValueT,
+		},
+        /// The value of a key is combined from fed values again.
+		ForcedValueCleared {
+            key: OracleKeyOf<T>,
+		},
+        /// The combined value of a key hasn't changed for `FrozenRounds` combines.
+		FeedFrozen {
+            key: OracleKeyOf<T>,
+            value: i64,
+		},
+        /// The combined value of a frozen key changed.
+		FeedUnfrozen {
+            key: OracleKeyOf<T>,
+		},
+        /// Apifeed is no longer fetched after `failures` consecutive fetch failures.
+		FeedAutoDisabled {
+			creator: CreatorId<T::AccountId>,
+            key: OracleKeyOf<T>,
+            failures: u32,
+		},
+        /// Spread between the highest and lowest fresh raw value of a key, see `EmitDispersion`.
+		FeedDispersion {
+            key: OracleKeyOf<T>,
+            spread: u64,
+		},
+        /// Operator removed its feeds and raw values.
+		OperatorDeregistered {
+			operator: CreatorId<T::AccountId>,
+            feeds_removed: u32,
+            raw_values_removed: u32,
+		},
+        /// Disabled apifeed is fetched again.
+		FeedEnabled {
+			sender: CreatorId<T::AccountId>,
+            key: OracleKeyOf<T>,
 		},
     }
 
@@ -579,7 +1580,38 @@ pub mod pallet {
         /// here we make sure that some particular calls (the ones produced by offchain worker)
         /// are being whitelisted and marked as valid.
         fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
-            InvalidTransaction::Call.into()
+            let (payload, signature) = match call {
+                Call::feed_data_unsigned { payload, signature } => (payload, signature),
+                _ => return InvalidTransaction::Call.into(),
+            };
+            if T::SubmissionMode::get() == SubmissionMode::SignedOnly {
+                return InvalidTransaction::Call.into();
+            }
+
+            let now = <system::Pallet<T>>::block_number();
+            if payload.block_number > now {
+                return InvalidTransaction::Future.into();
+            }
+            if payload.block_number.saturating_add(UNSIGNED_FEED_LONGEVITY.into()) < now {
+                return InvalidTransaction::Stale.into();
+            }
+            if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+                return InvalidTransaction::BadProof.into();
+            }
+            let feeder = payload.public.clone().into_account();
+            if !T::Members::contains(&feeder) {
+                return InvalidTransaction::BadSigner.into();
+            }
+            if Self::last_unsigned_feed(&feeder).map_or(false, |last| payload.block_number <= last) {
+                return InvalidTransaction::Stale.into();
+            }
+
+            ValidTransaction::with_tag_prefix("KylinOracleFeed")
+                .priority(T::UnsignedPriority::get())
+                .and_provides((feeder, payload.block_number))
+                .longevity(UNSIGNED_FEED_LONGEVITY.into())
+                .propagate(true)
+                .build()
         }
     }
 
@@ -589,73 +1621,12 @@ pub mod pallet {
 impl<T: Config> Pallet<T>
 where T::AccountId: AsRef<[u8]>
 {
-    fn choose_transaction_type(block_number: T::BlockNumber) -> TransactionType {
-        /// A friendlier name for the error that is going to be returned in case we are in the grace
-        /// period.
-        const RECENTLY_SENT: () = ();
-
-        // Start off by creating a reference to Local Storage value.
-        // Since the local storage is common for all offchain workers, it's a good practice
-        // to prepend your entry with the module name.
-        let val = StorageValueRef::persistent(b"kylin_oracle::last_send");
-        // The Local Storage is persisted and shared between runs of the offchain workers,
-        // and offchain workers may run concurrently. We can use the `mutate` function, to
-        // write a storage entry in an atomic fashion. Under the hood it uses `compare_and_set`
-        // low-level method of local storage API, which means that only one worker
-        // will be able to "acquire a lock" and send a transaction if multiple workers
-        // happen to be executed concurrently.
-        let res = val.mutate(
-            |last_send: Result<Option<T::BlockNumber>, StorageRetrievalError>| {
-                match last_send {
-                    // If we already have a value in storage and the block number is recent enough
-                    // we avoid sending another transaction at this time.
-                    Ok(Some(block)) if block_number < block => Err(RECENTLY_SENT),
-                    // In every other case we attempt to acquire the lock and send a transaction.
-                    _ => Ok(block_number),
-                }
-            },
-        );
-
-        // The result of `mutate` call will give us a nested `Result` type.
-        // The first one matches the return of the closure passed to `mutate`, i.e.
-        // if we return `Err` from the closure, we get an `Err` here.
-        // In case we return `Ok`, here we will have another (inner) `Result` that indicates
-        // if the value has been set to the storage correctly - i.e. if it wasn't
-        // written to in the meantime.
-        match res {
-            // The value has been set correctly, which means we can safely send a transaction now.
-            Ok(block_number) => {
-                // Depending if the block is even or odd we will send a `Signed` or `Unsigned`
-                // transaction.
-                // Note that this logic doesn't really guarantee that the transactions will be sent
-                // in an alternating fashion (i.e. fairly distributed). Depending on the execution
-                // order and lock acquisition, we may end up for instance sending two `Signed`
-                // transactions in a row. If a strict order is desired, it's better to use
-                // the storage entry for that. (for instance store both block number and a flag
-                // indicating the type of next transaction to send).
-                let transaction_type = block_number % 3u32.into();
-                if transaction_type == Zero::zero() {
-                    TransactionType::Signed
-                } else if transaction_type == T::BlockNumber::from(1u32) {
-                    TransactionType::UnsignedForAny
-                } else if transaction_type == T::BlockNumber::from(2u32) {
-                    TransactionType::UnsignedForAll
-                } else {
-                    TransactionType::Raw
-                }
-            }
-            // We are in the grace period, we should not send a transaction this time.
-            Err(MutateStorageError::ValueFunctionFailed(RECENTLY_SENT)) => TransactionType::None,
-            // We wanted to send a transaction, but failed to write the block number (acquire a
-            // lock). This indicates that another offchain worker that was running concurrently
-            // most likely executed the same logic and succeeded at writing to storage.
-            // Thus we don't really want to send the transaction, knowing that the other run
-            // already did.
-            Err(MutateStorageError::ConcurrentModification(_)) => TransactionType::None,
-        }
-    }
-
     /// A helper function to fetch the price and send signed transaction.
+    ///
+    /// The sweep resumes after the feed persisted in `kylin_oracle::sweep_cursor` and stops
+    /// once `OffchainFetchBudget` is spent, so the next block picks up where it left off.
+    /// Feeds are fetched in batches of up to `MaxConcurrentFetches` concurrent requests and
+    /// the budget is checked between batches, so at least one batch is fetched per run.
     fn fetch_api_and_feed_data(block_number: T::BlockNumber) -> Result<(), &'static str> {
         let signer = Signer::<T, T::AuthorityId>::all_accounts();
         if !signer.can_sign() {
@@ -664,98 +1635,308 @@ where T::AccountId: AsRef<[u8]>
             )?;
         }
 
+        let mut cursor = StorageValueRef::persistent(b"kylin_oracle::sweep_cursor");
+        let feeds = match cursor.get::<Vec<u8>>() {
+            Ok(Some(last_key)) => ApiFeeds::<T>::iter_from(last_key),
+            _ => ApiFeeds::<T>::iter(),
+        };
+        let started = sp_io::offchain::timestamp();
+        let budget = T::OffchainFetchBudget::get();
+        let mut last_key = None;
+        let mut out_of_budget = false;
+
+        let seed = Self::fetch_jitter_seed();
+        let interval = T::FetchInterval::get();
+        let block: u32 = block_number.unique_saturated_into();
+
+        let max_in_flight = T::MaxConcurrentFetches::get().max(1) as usize;
+        let mut batch = Vec::with_capacity(max_in_flight);
         let mut values = Vec::<(OracleKeyOf<T>, i64)>::new();
-        for (_creator, key, val) in <ApiFeeds<T> as IterableStorageDoubleMap<_, _, _>>::iter() {
-            // let mut response :Vec<u8>;
-            if val.url.is_some() && val.vpath.is_some() {
-                let vpath = val.vpath.unwrap();
-                let response = Self::fetch_http_get_result(val.url.clone().unwrap())
-                    .map_err(|_| "Failed fetch http")?;
-                let res_json :JValue = serde_json::from_slice(&response)
-                    .map_err(|_| "Response JSON was not well-formatted")?;
-                let path = str::from_utf8(&vpath)
-                    .map_err(|_| "vpath contain invalid utf8 string")?;
-                let fval = res_json.pointer(path)
-                    .ok_or("vpath error")?
-                    .as_f64()
-                    .ok_or("vpath value type error")?;
-
-                // We only store int, so every float will be convert to int with 6 decimals pad
-                let ival :i64 = (fval * 1000000.0) as i64;
-                values.push((key.clone(), ival));
+        let mut errors = Vec::<(CreatorId<T::AccountId>, OracleKeyOf<T>, FetchErrorKind)>::new();
+        let mut feeds = feeds.peekable();
+        while let Some((creator, key, val)) = feeds.next() {
+            if !val.disabled {
+                let feed_key = ApiFeeds::<T>::hashed_key_for(&creator, &key);
+                if Self::is_fetch_due(&seed, &feed_key, block, interval) {
+                    if let (Some(url), Some(vpath)) = (val.url, val.vpath) {
+                        if batch.is_empty() && last_key.is_some()
+                            && sp_io::offchain::timestamp().diff(&started).millis() >= budget
+                        {
+                            out_of_budget = true;
+                            break;
+                        }
+                        let boolean = Self::boolean_feeds(&creator, &key);
+                        batch.push((creator, key, feed_key, url.into_inner(), vpath, boolean));
+                    }
+                }
             }
+            if batch.is_empty() || (batch.len() < max_in_flight && feeds.peek().is_some()) {
+                continue;
+            }
+
+            // A failing feed is reported and doesn't hold back the others.
+            let requests: Vec<_> = batch
+                .iter()
+                .map(|(_, _, _, url, vpath, boolean)| (&url[..], &vpath[..], *boolean))
+                .collect();
+            let results = Self::fetch_feed_values(&requests);
+            for ((creator, key, feed_key, ..), result) in batch.drain(..).zip(results) {
+                match result {
+                    Ok(ival) => values.push((key, ival)),
+                    Err(kind) => {
+                        log::warn!("Failed to fetch feed {:?}: {:?}", key, kind);
+                        errors.push((creator, key, kind));
+                    }
+                }
+                last_key = Some(feed_key);
+            }
+        }
+
+        match last_key {
+            Some(last_key) if out_of_budget => cursor.set(&last_key),
+            _ => cursor.clear(),
         }
 
         if values.len() > 0 {
-            let results = signer.send_signed_transaction(|_account| Call::feed_data {
-                values: values.clone(),
-            });
+            Self::submit_values(block_number, values);
+        }
+        if errors.len() > 0 {
+            Self::submit_signed(Call::report_fetch_errors { errors });
+        }
+
+        Ok(())
+    }
+
+    /// Random per-node seed spreading feed fetches over `FetchInterval`, created on first use.
+    fn fetch_jitter_seed() -> [u8; 32] {
+        let storage = StorageValueRef::persistent(b"kylin_oracle::fetch_jitter_seed");
+        if let Ok(Some(seed)) = storage.get::<[u8; 32]>() {
+            return seed;
+        }
+        let seed = sp_io::offchain::random_seed();
+        storage.set(&seed);
+        seed
+    }
+
+    /// Whether a node with `seed` fetches the feed stored under `feed_key` at `block_number`.
+    fn is_fetch_due(seed: &[u8; 32], feed_key: &[u8], block_number: u32, interval: u32) -> bool {
+        let interval = interval.max(1);
+        let hash = sp_io::hashing::blake2_128(&[&seed[..], feed_key].concat());
+        let offset = u32::from_le_bytes([hash[0], hash[1], hash[2], hash[3]]) % interval;
+        block_number % interval == offset
+    }
+
+    /// Fetch each `(url, vpath, boolean)` concurrently and resolve `vpath` in its JSON body.
+    fn fetch_feed_values(feeds: &[(&[u8], &[u8], bool)]) -> Vec<Result<i64, FetchErrorKind>> {
+        let urls: Vec<&[u8]> = feeds.iter().map(|(url, _, _)| *url).collect();
+        Self::fetch_http_get_results(&urls, T::MaxResponseBytes::get() as usize)
+            .into_iter()
+            .zip(feeds)
+            .map(|(body, (_, vpath, boolean))| {
+                body.map_err(|_| FetchErrorKind::Http)
+                    .and_then(|body| Self::parse_feed_value(&body, vpath, *boolean))
+            })
+            .collect()
+    }
+
+    /// Fetch every url concurrently and return the raw body of each, giving up on a body
+    /// once it exceeds `max_bytes`.
+    ///
+    /// Every request runs against its own deadline, one that times out or fails only
+    /// fails its own url. The bodies are returned as is, parsers like
+    /// `serde_json::from_slice` work on the bytes.
+    fn fetch_http_get_results(urls: &[&[u8]], max_bytes: usize) -> Vec<Result<Vec<u8>, http::Error>> {
+        let mut results: Vec<_> = urls.iter().map(|_| Err(http::Error::IoError)).collect();
+        let mut pending = Vec::with_capacity(urls.len());
+        let mut latest_deadline = sp_io::offchain::timestamp();
+        for (i, url) in urls.iter().enumerate() {
+            let deadline = Self::http_deadline();
+            latest_deadline = latest_deadline.max(deadline);
+            match Self::start_http_get(url, deadline) {
+                Ok(request) => pending.push((i, request)),
+                Err(e) => log::info!("Failed to send request: {:?}", e),
+            }
+        }
+
+        let (slots, requests): (Vec<_>, Vec<_>) = pending.into_iter().unzip();
+        let responses = http::PendingRequest::try_wait_all(requests, latest_deadline);
+        for (i, response) in slots.into_iter().zip(responses) {
+            results[i] = match response {
+                Ok(Ok(response)) => Self::read_http_body(response, max_bytes),
+                Ok(Err(e)) => Err(e),
+                Err(_) => Err(http::Error::DeadlineReached),
+            };
+        }
+        results
+    }
+
+    /// Resolve `vpath` in the JSON `body` of a feed's response.
+    fn parse_feed_value(body: &[u8], vpath: &[u8], boolean: bool) -> Result<i64, FetchErrorKind> {
+        let res_json :JValue = serde_json::from_slice(body)
+            .map_err(|_| FetchErrorKind::InvalidJson)?;
+        let path = str::from_utf8(vpath).map_err(|_| FetchErrorKind::InvalidPath)?;
+        Self::extract_value(&res_json, path, boolean)
+    }
+
+    /// Submit fed values with the transactions chosen by `SubmissionMode`.
+    fn submit_values(block_number: T::BlockNumber, values: Vec<(OracleKeyOf<T>, i64)>) {
+        match T::SubmissionMode::get() {
+            SubmissionMode::SignedOnly => {
+                Self::submit_signed(Call::feed_data { values });
+            },
+            SubmissionMode::UnsignedOnly => Self::submit_unsigned(block_number, values),
+            SubmissionMode::Either => {
+                if !Self::submit_signed(Call::feed_data { values: values.clone() }) {
+                    log::warn!("No signed transaction was accepted, feeding unsigned");
+                    Self::submit_unsigned(block_number, values);
+                }
+            },
+        }
+    }
+
+    /// Sign and submit `call` with the key(s) chosen by `SignWithAllAccounts`.
+    ///
+    /// Returns whether any transaction was accepted.
+    fn submit_signed(call: Call<T>) -> bool {
+        if T::SignWithAllAccounts::get() {
+            let signer = Signer::<T, T::AuthorityId>::all_accounts();
+            let results = signer.send_signed_transaction(|_account| call.clone());
             for (acc, res) in &results {
                 match res {
                     Ok(()) => log::info!("[{:?}] Submitted data", acc.id),
                     Err(e) => log::error!("[{:?}] Failed to submit transaction: {:?}", acc.id, e),
                 }
             }
+            return results.iter().any(|(_, res)| res.is_ok());
         }
 
-        Ok(())
+        match Self::local_signer().send_signed_transaction(|_account| call.clone()) {
+            Some((acc, Ok(()))) => {
+                log::info!("[{:?}] Submitted data", acc.id);
+                true
+            },
+            Some((acc, Err(e))) => {
+                log::error!("[{:?}] Failed to submit transaction: {:?}", acc.id, e);
+                false
+            },
+            None => {
+                log::error!("No local account matches the configured signing account");
+                false
+            },
+        }
+    }
+
+    /// Submit `values` in an unsigned transaction, signed by a single local key chosen like
+    /// for `submit_signed` without `SignWithAllAccounts`.
+    fn submit_unsigned(block_number: T::BlockNumber, values: Vec<(OracleKeyOf<T>, i64)>) {
+        let result = Self::local_signer().send_unsigned_transaction(
+            |account| FeedPayload {
+                public: account.public.clone(),
+                block_number,
+                values: values.clone(),
+            },
+            |payload, signature| Call::feed_data_unsigned { payload, signature },
+        );
+        match result {
+            Some((acc, Ok(()))) => log::info!("[{:?}] Submitted unsigned data", acc.id),
+            Some((acc, Err(e))) => log::error!("[{:?}] Failed to submit unsigned transaction: {:?}", acc.id, e),
+            None => log::error!("No local account matches the configured signing account"),
+        }
+    }
+
+    /// Any local key, or the one stored under `kylin_oracle::signing_account` if present.
+    fn local_signer() -> Signer<T, T::AuthorityId, ForAny> {
+        let signer = Signer::<T, T::AuthorityId>::any_account();
+        let preferred = StorageValueRef::persistent(b"kylin_oracle::signing_account");
+        match preferred.get::<T::Public>() {
+            Ok(Some(public)) => signer.with_filter(vec![public]),
+            _ => signer,
+        }
     }
     
-    /// Fetch current price and return the result in cents.
-    fn fetch_http_get_result(url: Vec<u8>) -> Result<Vec<u8>, http::Error> {
+    /// Deadline for an HTTP request started now.
+    fn http_deadline() -> Timestamp {
         // We want to keep the offchain worker execution time reasonable, so we set a hard-coded
         // deadline to 2s to complete the external call.
         // You can also wait idefinitely for the response, however you may still get a timeout
         // coming from the host machine.
-        let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(10_000));
+        sp_io::offchain::timestamp().add(Duration::from_millis(10_000))
+    }
+
+    /// Send an HTTP GET request for `url` that has to complete by `deadline`.
+    fn start_http_get(url: &[u8], deadline: Timestamp) -> Result<http::PendingRequest, http::Error> {
         // Initiate an external HTTP GET request.
         // This is using high-level wrappers from `sp_runtime`, for the low-level calls that
         // you can find in `sp_io`. The API is trying to be similar to `reqwest`, but
         // since we are running in a custom WASM execution environment we can't simply
         // import the library here.
-        let request = http::Request::get(str::from_utf8(&url).unwrap());
+        let url = str::from_utf8(url).map_err(|_| http::Error::Unknown)?;
+        let request = http::Request::get(url);
 
         // We set the deadline for sending of the request, note that awaiting response can§
         // have a separate deadline. Next we send the request, before that it's also possible
         // to alter request headers or stream body content in case of non-GET requests.
-        let pending = request
+        request
             .deadline(deadline)
             .send()
-            .map_err(|_| http::Error::IoError)?;
-
-        // The request is already being processed by the host, we are free to do anything
-        // else in the worker (we can send multiple concurrent requests too).
-        // At some point however we probably want to check the response though,
-        // so we can block current thread and wait for it to finish.
-        // Note that since the request is being driven by the host, we don't have to wait
-        // for the request to have it complete, we will just not read the response.
-        let response = pending
-            .try_wait(deadline)
-            .map_err(|_| http::Error::DeadlineReached)??;
+            .map_err(|_| http::Error::IoError)
+    }
 
+    /// Read the body of a successful `response`, giving up beyond `max_bytes`.
+    fn read_http_body(response: http::Response, max_bytes: usize) -> Result<Vec<u8>, http::Error> {
         // Let's check the status code before we proceed to reading the response.
         if response.code != 200 {
             log::info!("Unexpected status code: {}", response.code);
             return Err(http::Error::Unknown);
         }
 
-        // Next we want to fully read the response body and collect it to a vector of bytes.
-        // Note that the return object allows you to read the body in chunks as well
-        // with a way to control the deadline.
-        let body = response.body().collect::<Vec<u8>>();
-        // Create a str slice from the body.
-        let body_str = sp_std::str::from_utf8(&body).map_err(|_| {
-            log::info!("No UTF8 body");
-            http::Error::Unknown
-        })?;
+        // A declared length over budget fails right away, otherwise it sizes the buffer.
+        let content_length = response
+            .headers()
+            .find("content-length")
+            .and_then(|len| len.parse::<usize>().ok());
+        if content_length.map_or(false, |len| len > max_bytes) {
+            log::info!("Response body exceeds {} bytes", max_bytes);
+            return Err(http::Error::Unknown);
+        }
 
-        Ok(body_str.clone().as_bytes().to_vec())
+        // Next we want to read the response body and collect it to a vector of bytes.
+        // The body is consumed chunk by chunk, so we can give up as soon as it grows
+        // beyond `max_bytes` instead of buffering an arbitrarily large response.
+        let mut body = Vec::<u8>::with_capacity(content_length.unwrap_or(0));
+        for byte in response.body() {
+            if body.len() >= max_bytes {
+                log::info!("Response body exceeds {} bytes", max_bytes);
+                return Err(http::Error::Unknown);
+            }
+            body.push(byte);
+        }
+
+        Ok(body)
     }
 
-    fn send_qret_to_parachain(para_id: ParaId, key: Vec<u8>, value: i64) -> DispatchResult {
-        let remark = KylinMockCall::KylinFeed(KylinMockFunc::xcm_feed_back{
-            key, value,
-        });
+    fn send_qret_to_parachain(para_id: ParaId, key: Vec<u8>, value: i64, decimals: u8) -> DispatchResult {
+        let oracle_key = OracleKeyOf::<T>::try_from(key.clone()).ok();
+        let pair = oracle_key
+            .as_ref()
+            .and_then(|key| Self::value_pairs(key))
+            .map(|pair| (pair.base.into_inner(), pair.quote.into_inner()));
+        // A retried answer may carry an older value than the one the confidence was recorded for.
+        let confidence = oracle_key
+            .as_ref()
+            .filter(|key| Self::values(key).map_or(false, |published| published.value == value))
+            .and_then(|key| Self::value_confidence(key));
+        let feedback = FeedbackCall {
+            pallet_index: T::FeedbackPalletIndex::get(),
+            call_index: T::FeedbackCallIndex::get(),
+            key, value, decimals, pair, confidence,
+        };
+        let call = feedback.encode();
+        // The destination is expected to dispatch this as `KylinFeed::xcm_feed_back` (pallet
+        // 168, call 7 in the Kylin runtime), so it has to lead with the configured indices
+        // and carry nothing but the call's arguments after them.
+        debug_assert_eq!(&call[..2], &[feedback.pallet_index, feedback.call_index]);
+        debug_assert_eq!(FeedbackCall::decode(&mut &call[..]).ok().as_ref(), Some(&feedback));
         T::XcmSender::send_xcm(
             (
                 1,
@@ -764,7 +1945,7 @@ where T::AccountId: AsRef<[u8]>
             Xcm(vec![Transact {
                 origin_type: OriginKind::Native,
                 require_weight_at_most: 1_000_000_000,
-                call: remark.encode().into(),
+                call: call.into(),
             }]),
         ).map_err(
             |e| {
@@ -776,22 +1957,57 @@ where T::AccountId: AsRef<[u8]>
         Ok(())
     }
 
-    fn validate_transaction(block_number: &T::BlockNumber) -> TransactionValidity {
-        // Now let's check if the transaction has any chance to succeed.
-        let next_unsigned_at = <NextUnsignedAt<T>>::get();
-        if &next_unsigned_at > block_number {
-            return InvalidTransaction::Stale.into();
+    /// Resolve `path` in `json` to the integer fed for it.
+    ///
+    /// Booleans become `1`/`0` only for feeds flagged as boolean, so a numeric feed pointed at
+    /// the wrong field still errors instead of publishing a bogus price.
+    fn extract_value(json: &JValue, path: &str, boolean: bool) -> Result<i64, FetchErrorKind> {
+        let value = json.pointer(path).ok_or(FetchErrorKind::InvalidPath)?;
+        if boolean {
+            if let Some(flag) = value.as_bool() {
+                return Ok(flag as i64);
+            }
         }
-        // Let's make sure to reject transactions from the future.
-        let current_block = <system::Pallet<T>>::block_number();
-        if &current_block < block_number {
-            return InvalidTransaction::Future.into();
+        let fval = value.as_f64().ok_or(FetchErrorKind::InvalidValue)?;
+
+        // We only store int, so every float will be convert to int with `VALUE_DECIMALS` pad
+        Ok((fval * 10_i64.pow(VALUE_DECIMALS as u32) as f64) as i64)
+    }
+
+    /// Re-send queued query answers, dropping the ones that exhausted their retries.
+    ///
+    /// Returns the number of answers attempted.
+    fn retry_pending_responses() -> u32 {
+        let pending = PendingResponses::<T>::get();
+        if pending.is_empty() {
+            return 0;
         }
-        ValidTransaction::with_tag_prefix("KylinOCW")
-            .priority(T::UnsignedPriority::get())
-            .longevity(5)
-            .propagate(true)
-            .build()
+        let attempted = pending.len() as u32;
+        let mut remaining = Vec::new();
+        for mut response in pending.into_iter() {
+            if Self::send_qret_to_parachain(
+                response.para_id,
+                response.key.clone(),
+                response.value,
+                response.decimals,
+            )
+            .is_ok()
+            {
+                continue;
+            }
+            response.retries = response.retries.saturating_add(1);
+            if response.retries >= T::MaxResponseRetries::get() {
+                Self::deposit_event(Event::QueryAnswerAbandoned {
+                    para_id: response.para_id,
+                    key: response.key,
+                });
+            } else {
+                remaining.push(response);
+            }
+        }
+        // Can't exceed the bound, `remaining` is a subset of what was stored.
+        PendingResponses::<T>::put(BoundedVec::try_from(remaining).unwrap_or_default());
+        attempted
     }
 
     pub fn read_raw_values(key: &OracleKeyOf<T>) -> Vec<TimestampedValueT> {
@@ -808,9 +2024,34 @@ where T::AccountId: AsRef<[u8]>
         // v0.append(&mut v1);
         // v0
 
-        <RawValues<T> as IterableStorageDoubleMap<_, _, _>>::iter()
-            .filter_map(|(_, k, val)| if *key == k { Some(val) } else { None })
-            .collect()
+        Self::read_raw_values_by_creator(key).into_iter().map(|(_, val)| val).collect()
+	}
+
+	/// Raw values for `key` along with the operator that reported them, sorted by operator.
+	pub fn read_raw_values_by_creator(
+		key: &OracleKeyOf<T>,
+	) -> Vec<(CreatorId<T::AccountId>, TimestampedValueT)> {
+        // Storage iteration order follows the hashed keys, so sort by creator to keep
+        // the combine input independent of it.
+        let mut raws: Vec<(CreatorId<T::AccountId>, TimestampedValueT)> =
+            <RawValues<T> as IterableStorageDoubleMap<_, _, _>>::iter()
+                .filter_map(|(cid, k, val)| if *key == k { Some((cid, val)) } else { None })
+                .collect();
+        raws.sort_by(|a, b| a.0.cmp(&b.0));
+        raws
+	}
+
+	/// Spread between the highest and the lowest raw value of `key` younger than
+	/// `MaxRawValueAge`, `None` without such values.
+	pub fn dispersion(key: &OracleKeyOf<T>) -> Option<u64> {
+		let now = T::UnixTime::now().as_millis();
+		let max_age = T::MaxRawValueAge::get();
+		let values: Vec<i64> = Self::read_raw_values(key)
+			.into_iter()
+			.filter(|x| x.timestamp.saturating_add(max_age) >= now)
+			.map(|x| x.value)
+			.collect();
+		Some(values.iter().max()?.abs_diff(*values.iter().min()?))
 	}
 
 	/// Fetch current combined value.
@@ -818,34 +2059,508 @@ where T::AccountId: AsRef<[u8]>
 		Self::values(key)
 	}
 
+	/// Fetch current combined value, unless it is older than `MaxRawValueAge` or, with
+	/// `SuppressFrozen`, its key is frozen.
+	pub fn get_fresh(key: &OracleKeyOf<T>) -> Option<TimestampedValueT> {
+		if T::SuppressFrozen::get() && Self::is_frozen(key) {
+			return None;
+		}
+		let now = T::UnixTime::now().as_millis();
+		Self::values(key).filter(|v| v.timestamp.saturating_add(T::MaxRawValueAge::get()) > now)
+	}
+
+	/// Whether the combined value of `key` hasn't changed for `FrozenRounds` combines.
+	pub fn is_frozen(key: &OracleKeyOf<T>) -> bool {
+		let rounds = T::FrozenRounds::get();
+		rounds > 0 && Self::unchanged_rounds(key) >= rounds
+	}
+
+	/// Fetch current combined value along with its decimal places.
+	pub fn get_with_decimals(key: &OracleKeyOf<T>) -> Option<(TimestampedValueT, u8)> {
+		let value = Self::values(key)?;
+		Some((value, Self::value_decimals(key).unwrap_or(VALUE_DECIMALS)))
+	}
+
+	/// Fetch current combined value along with the confidence recorded for it, if any.
+	pub fn get_with_confidence(key: &OracleKeyOf<T>) -> Option<(TimestampedValueT, Option<Permill>)> {
+		let value = Self::values(key)?;
+		Some((value, Self::value_confidence(key)))
+	}
+
+	/// How closely the raw values combined into `value` agree: one minus their `spread`
+	/// relative to `value`, so reports 1% apart yield 99%.
+	pub fn confidence(value: i64, spread: u64) -> Permill {
+		match spread {
+			0 => Permill::one(),
+			spread => Permill::from_rational(spread, value.unsigned_abs()).left_from_one(),
+		}
+	}
+
+	/// Fetch current combined value along with the base and quote asset it is denominated in.
+	pub fn get_with_pair(key: &OracleKeyOf<T>) -> Option<(TimestampedValueT, Option<ValuePairOf<T>>)> {
+		let value = Self::values(key)?;
+		Some((value, Self::value_pairs(key)))
+	}
+
+	/// Combined value of `key` along with every operator's raw value, sorted by creator.
+	#[allow(clippy::type_complexity)]
+	pub fn get_with_raws(
+		key: &OracleKeyOf<T>,
+	) -> (Option<TimestampedValueT>, Vec<(CreatorId<T::AccountId>, TimestampedValueT)>) {
+		(Self::get(key), Self::read_raw_values_by_creator(key))
+	}
+
 	#[allow(clippy::complexity)]
 	pub fn get_all_values() -> Vec<(OracleKeyOf<T>, Option<TimestampedValueT>)> {
 		<Values<T>>::iter().map(|(k, v)| (k, Some(v))).collect()
 	}
 
+	/// Feeds, combined values and raw values of up to `max_keys` keys following `from`.
+	///
+	/// `max_keys` is capped at `MAX_SNAPSHOT_KEYS`, start with `from = None` and follow
+	/// `next` to export the whole oracle.
+	#[allow(clippy::type_complexity)]
+	pub fn snapshot(
+		from: Option<Vec<u8>>,
+		max_keys: u32,
+	) -> OracleSnapshot<CreatorId<T::AccountId>, ApiFeedOf<T>, TimestampedValueT> {
+		let after = |key: &OracleKeyOf<T>| from.as_ref().map_or(true, |from| key.as_slice() > from.as_slice());
+		let mut keys: Vec<OracleKeyOf<T>> = <ApiFeeds<T>>::iter_keys()
+			.map(|(_, key)| key)
+			.chain(<Values<T>>::iter_keys())
+			.chain(<RawValues<T>>::iter_keys().map(|(_, key)| key))
+			.filter(|key| after(key))
+			.collect();
+		keys.sort();
+		keys.dedup();
+
+		let max_keys = max_keys.min(MAX_SNAPSHOT_KEYS) as usize;
+		let next = if keys.len() > max_keys {
+			keys.truncate(max_keys);
+			keys.last().map(|key| key.to_vec())
+		} else {
+			None
+		};
+		let in_page = |key: &OracleKeyOf<T>| keys.binary_search(key).is_ok();
+
+		let mut feeds: Vec<_> = <ApiFeeds<T>>::iter()
+			.filter(|(_, key, _)| in_page(key))
+			.map(|(creator, key, feed)| (creator, key.into_inner(), feed))
+			.collect();
+		feeds.sort_by(|a, b| (&a.1, &a.0).cmp(&(&b.1, &b.0)));
+		let mut values: Vec<_> = <Values<T>>::iter()
+			.filter(|(key, _)| in_page(key))
+			.map(|(key, value)| (key.into_inner(), value))
+			.collect();
+		values.sort_by(|a, b| a.0.cmp(&b.0));
+		let mut raw_values: Vec<_> = <RawValues<T>>::iter()
+			.filter(|(_, key, _)| in_page(key))
+			.map(|(creator, key, value)| (creator, key.into_inner(), value))
+			.collect();
+		raw_values.sort_by(|a, b| (&a.1, &a.0).cmp(&(&b.1, &b.0)));
+
+		OracleSnapshot { version: SNAPSHOT_VERSION, feeds, values, raw_values, next }
+	}
+
+	/// Fetch health of every feed, by its creator and key.
+	pub fn oracle_diagnostics() -> Vec<(CreatorId<T::AccountId>, OracleKeyOf<T>, FeedHealth<T::AccountId, T::BlockNumber>)> {
+		<FeedHealths<T>>::iter().collect()
+	}
+
+	/// Every feed registered by `creator`, keyed by oracle key.
+	pub fn feeds_of(creator: CreatorId<T::AccountId>) -> Vec<(OracleKeyOf<T>, ApiFeedOf<T>)> {
+		<ApiFeeds<T>>::iter_prefix(&creator).collect()
+	}
+
+	/// Where the feed `key` of `creator` is fetched from, or `None` if there's no such feed.
+	pub fn feed_config(creator: CreatorId<T::AccountId>, key: &OracleKeyOf<T>) -> Option<ApiFeedInfo> {
+		let feed = Self::api_feeds(&creator, key)?;
+		Some(ApiFeedInfo {
+			url: feed.url.map(|url| url.into_inner()),
+			vpath: feed.vpath.map(|vpath| vpath.into_inner()),
+			min: feed.min,
+			max: feed.max,
+			fetch_interval: T::FetchInterval::get(),
+			decimals: if Self::boolean_feeds(&creator, key) { 0 } else { VALUE_DECIMALS },
+			disabled: feed.disabled,
+		})
+	}
+
+	/// How long ago (in millis) each combined value was updated, relative to `now`.
+	///
+	/// Sorted by key so the result doesn't depend on the storage hasher.
+	pub fn feed_lags(now: u128) -> Vec<(Vec<u8>, u128)> {
+		let mut lags: Vec<(Vec<u8>, u128)> = <Values<T>>::iter()
+			.map(|(k, v)| (k.into_inner(), now.saturating_sub(v.timestamp)))
+			.collect();
+		lags.sort_by(|a, b| a.0.cmp(&b.0));
+		lags
+	}
+
+	/// Estimated fee of answering an XCM query for `key`, as charged by `EstimateCallFee`.
+	///
+	/// The fee is the same whichever parachain sends the query.
+	pub fn query_response_fee(key: OracleKeyOf<T>) -> BalanceOf<T> {
+		let call = Call::<T>::xcm_query_data { key };
+		T::EstimateCallFee::estimate_call_fee(&call, Default::default())
+	}
+
 	fn combined(key: &OracleKeyOf<T>) -> Option<TimestampedValueT> {
-		let values = Self::read_raw_values(key);
-		T::CombineData::combine_data(key, values, Self::values(key))
+		Self::combined_with_provenance(key).map(|(value, _, _)| value)
+	}
+
+	/// Track how many combines in a row left the value of `key` unchanged, and emit
+	/// `FeedFrozen`/`FeedUnfrozen` when the key crosses `FrozenRounds`.
+	fn note_combined_round(key: &OracleKeyOf<T>, combined: &TimestampedValueT) {
+		let rounds = T::FrozenRounds::get();
+		if rounds == 0 {
+			return;
+		}
+		let unchanged = Self::values(key).map_or(false, |prev| prev.value == combined.value);
+		if unchanged {
+			let count = UnchangedRounds::<T>::mutate(key, |count| {
+				*count = count.saturating_add(1);
+				*count
+			});
+			if count == rounds {
+				Self::deposit_event(Event::FeedFrozen { key: key.clone(), value: combined.value });
+			}
+		} else {
+			let was_frozen = Self::is_frozen(key);
+			UnchangedRounds::<T>::remove(key);
+			if was_frozen {
+				Self::deposit_event(Event::FeedUnfrozen { key: key.clone() });
+			}
+		}
+	}
+
+	/// Combine the fresh raw values of `key`, also returning the operators whose report
+	/// is the combined value and the spread of the raw values it was combined from.
+	///
+	/// The result isn't cached. A combine only runs right after a raw value of `key` changed,
+	/// which would invalidate any cached result, and reads are served from `Values`, which
+	/// already holds the last combine.
+	#[allow(clippy::type_complexity)]
+	fn combined_with_provenance(
+		key: &OracleKeyOf<T>,
+	) -> Option<(TimestampedValueT, Vec<CreatorId<T::AccountId>>, u64)> {
+		if Self::forced_values(key) {
+			return None;
+		}
+		let now = T::UnixTime::now().as_millis();
+		let max_age = T::MaxRawValueAge::get();
+		let raws: Vec<(CreatorId<T::AccountId>, TimestampedValueT)> =
+			Self::read_raw_values_by_creator(key)
+				.into_iter()
+				.filter(|(_, x)| x.timestamp.saturating_add(max_age) >= now)
+				.collect();
+		if T::StrictAllOperators::get() {
+			let all_reported = T::Members::sorted_members().into_iter().all(|member| {
+				let member = CreatorId::AccountId(member);
+				raws.iter().any(|(cid, _)| *cid == member)
+			});
+			if !all_reported {
+				return None;
+			}
+		}
+		let prev_value = Self::values(key);
+		let strategy = Self::combine_strategies(key);
+		let combine = |values: Vec<TimestampedValueT>, prev_value| match strategy {
+			Some(strategy) => strategy.combine(values, now),
+			None => T::CombineData::combine_data(key, values, prev_value),
+		};
+		let groups = Self::source_groups(key);
+		let (combined, raws) = if groups.is_empty() {
+			let values = raws.iter().map(|(_, x)| *x).collect();
+			(combine(values, prev_value)?, raws)
+		} else {
+			// Without a previous value to fall back to, a group lacking quorum yields `None`
+			// and the next group is tried.
+			groups.iter().find_map(|group| {
+				let group_raws: Vec<(CreatorId<T::AccountId>, TimestampedValueT)> =
+					raws.iter().filter(|(cid, _)| group.contains(cid)).cloned().collect();
+				let values = group_raws.iter().map(|(_, x)| *x).collect();
+				combine(values, None).map(|combined| (combined, group_raws))
+			})?
+		};
+
+		// Falling back to the previous value means there is nothing new to publish.
+		if Some(combined) == prev_value {
+			return None;
+		}
+
+		// The confidence only describes the raw values combined, i.e. those of the group used.
+		let combined_values = raws.iter().map(|(_, x)| x.value);
+		let spread = combined_values
+			.clone()
+			.max()
+			.zip(combined_values.min())
+			.map_or(0, |(max, min)| max.abs_diff(min));
+
+		// An averaged value is contributed to by every operator combined.
+		let averaging =
+			strategy.map_or_else(T::CombineData::is_averaging, |strategy| strategy.is_averaging());
+		let contributors = raws
+			.into_iter()
+			.filter(|(_, x)| averaging || *x == combined)
+			.map(|(cid, _)| cid)
+			.collect();
+		Some((combined, contributors, spread))
 	}
 
+    #[allow(clippy::too_many_arguments)]
     pub fn do_submit_api(
         cid: CreatorId<T::AccountId>,
         key: OracleKeyOf<T>,
         url: Vec<u8>,
         vpath: Vec<u8>,
+        min: Option<i64>,
+        max: Option<i64>,
+        pair: Option<(Vec<u8>, Vec<u8>)>,
+        allowlist: Option<Vec<CreatorId<T::AccountId>>>,
     ) -> DispatchResult {
+        let url: BoundedVec<u8, T::MaxUrlLen> = url.try_into().map_err(|_| Error::<T>::TooLarge)?;
+        let vpath: BoundedVec<u8, T::MaxVPathLen> = vpath.try_into().map_err(|_| Error::<T>::TooLarge)?;
+        if let (Some(min), Some(max)) = (min, max) {
+            ensure!(min <= max, Error::<T>::InvalidValueBounds);
+        }
+        let pair: Option<ValuePairOf<T>> = pair
+            .map(|(base, quote)| -> Result<_, Error<T>> {
+                Ok(ValuePair {
+                    base: base.try_into().map_err(|_| Error::<T>::TooLarge)?,
+                    quote: quote.try_into().map_err(|_| Error::<T>::TooLarge)?,
+                })
+            })
+            .transpose()?;
+        let allowlist: Option<AllowlistOf<T>> = allowlist
+            .map(|operators| operators.try_into().map_err(|_| Error::<T>::TooManyAllowedOperators))
+            .transpose()?;
+        if pair.is_some() {
+            // The pair describes the key's published value, so only the key's owner may tag it.
+            let owned_by_other = Self::feed_owners(&key).map_or(false, |owner| owner != cid);
+            ensure!(!owned_by_other, Error::<T>::NoPermission);
+        }
+        if allowlist.is_some() {
+            // Only the creator that restricted the key may replace its allowlist.
+            let restricted_by_other = Self::feed_allowlist(&key).map_or(false, |(owner, _)| owner != cid);
+            ensure!(!restricted_by_other, Error::<T>::NoPermission);
+        }
+        Self::ensure_feed_capacity(&cid, &key)?;
+
         let block_number = <system::Pallet<T>>::block_number();
         let feed = ApiFeed {
                 requested_block_number: block_number,
                 url: Some(url),
                 vpath: Some(vpath),
+                min,
+                max,
+                disabled: false,
             };
         ApiFeeds::<T>::insert(&cid, &key, feed.clone());
+        if !<FeedOwners<T>>::contains_key(&key) {
+            <FeedOwners<T>>::insert(&key, cid.clone());
+        }
+        if let Some(pair) = pair {
+            <ValuePairs<T>>::insert(&key, pair);
+        }
+        if let Some(allowlist) = allowlist {
+            <FeedAllowlists<T>>::insert(&key, (cid.clone(), allowlist));
+        }
 
         Self::deposit_event(Event::NewApiFeed { sender: cid, key, feed });
         Ok(())
     }
 
+    /// Disable the feed `key` of `creator`, if it exists and isn't disabled yet.
+    fn auto_disable_feed(creator: CreatorId<T::AccountId>, key: OracleKeyOf<T>, failures: u32) {
+        let disabled = ApiFeeds::<T>::mutate(&creator, &key, |feed| match feed {
+            Some(feed) if !feed.disabled => {
+                feed.disabled = true;
+                true
+            }
+            _ => false,
+        });
+        if disabled {
+            log::warn!("Disabled feed {:?} after {} consecutive fetch failures", key, failures);
+            Self::deposit_event(Event::FeedAutoDisabled { creator, key, failures });
+        }
+    }
+
+    /// Store the values fed by `feeder` and publish the keys whose combine yields a value.
+    fn do_feed_data(
+        feeder: T::AccountId,
+        values: Vec<(OracleKeyOf<T>, i64)>,
+    ) -> DispatchResultWithPostInfo {
+        let cid = CreatorId::AccountId(feeder.clone());
+        // ensure feeder is authorized
+        ensure!(T::Members::contains(&feeder), Error::<T>::NoPermission);
+
+        // ensure account hasn't dispatched an updated yet
+        Self::mark_dispatched(&cid)?;
+
+        let now = T::UnixTime::now().as_millis();
+        let block_number = <system::Pallet<T>>::block_number();
+        let deadband = T::UpdateDeadband::get();
+        let refresh_age = T::MaxRawValueAge::get() / 2;
+        let mut applied = Vec::with_capacity(values.len());
+        for (key, value) in values {
+            if !Self::is_allowlisted(&cid, &key) {
+                applied.push((key, value, FeedStatus::NotAllowlisted));
+                continue;
+            }
+            let value = match Self::bound_value(&key, value) {
+                Some(value) => value,
+                None => {
+                    applied.push((key, value, FeedStatus::OutOfBounds));
+                    continue;
+                },
+            };
+            // Skip negligible changes, but refresh the raw value before it could expire.
+            let negligible = Self::raw_values(&cid, &key).map_or(false, |prev| {
+                prev.value.abs_diff(value) < deadband
+                    && prev.timestamp.saturating_add(refresh_age) > now
+            });
+            // A fed value counts as a success of the feed that owns the key.
+            let owner = Self::feed_owners(&key);
+            if negligible {
+                // A steady feed keeps its health untouched, unless failures are to be cleared.
+                if let Some(owner) = owner {
+                    if Self::feed_health(&owner, &key).consecutive_failures > 0 {
+                        FeedHealths::<T>::mutate(&owner, &key, |health| health.reset_failures());
+                    }
+                }
+                continue;
+            }
+            if let Some(owner) = owner {
+                FeedHealths::<T>::mutate(&owner, &key, |health| {
+                    health.last_success = Some(block_number);
+                    health.reset_failures();
+                });
+            }
+
+            let timestamped = TimestampedValue {
+                value,
+                timestamp: now,
+            };
+            Self::insert_raw_value(&cid, &key, timestamped);
+            T::OnNewValue::on_new_data(&cid, &key, &value);
+            Self::note_dispersion(&key);
+
+            // Update `Values` storage if `combined` yielded result.
+            let status = match Self::combined_with_provenance(&key) {
+                Some((combined, contributors, spread)) => {
+                    Self::note_combined_round(&key, &combined);
+                    <Values<T>>::insert(&key, combined);
+                    <ValueDecimals<T>>::insert(&key, Self::decimals_of(&key));
+                    Self::note_confidence(&key, &combined, spread);
+                    T::ValueSink::on_value_published(&key, combined.value, combined.timestamp);
+                    Self::deposit_event(Event::ValueCombined {
+                        key: key.clone(),
+                        value: combined,
+                        contributors,
+                    });
+                    FeedStatus::Published
+                },
+                None => FeedStatus::PendingQuorum,
+            };
+            applied.push((key, value, status));
+        }
+
+        if !applied.is_empty() {
+            Self::deposit_event(Event::NewFeedData { sender: cid, values: applied });
+        }
+        Ok(Pays::No.into())
+    }
+
+    /// Record the confidence in the value just published for `key` if `StoreConfidence` is set.
+    fn note_confidence(key: &OracleKeyOf<T>, value: &TimestampedValueT, spread: u64) {
+        if T::StoreConfidence::get() {
+            <ValueConfidence<T>>::insert(key, Self::confidence(value.value, spread));
+        }
+    }
+
+    /// Emit `FeedDispersion` for `key` if `EmitDispersion` is set.
+    fn note_dispersion(key: &OracleKeyOf<T>) {
+        if T::EmitDispersion::get() {
+            if let Some(spread) = Self::dispersion(key) {
+                Self::deposit_event(Event::FeedDispersion { key: key.clone(), spread });
+            }
+        }
+    }
+
+    /// Record that `cid` fed in this block.
+    fn mark_dispatched(cid: &CreatorId<T::AccountId>) -> Result<(), Error<T>> {
+        HasDispatched::<T>::try_mutate(|set| {
+            ensure!(!set.contains(cid), Error::<T>::AlreadyFeeded);
+            ensure!(set.insert(cid.clone()), Error::<T>::DispatchSetFull);
+            Ok(())
+        })
+    }
+
+    /// Store the raw value `cid` reported for `key`, counting it if it is the first one.
+    fn insert_raw_value(cid: &CreatorId<T::AccountId>, key: &OracleKeyOf<T>, value: TimestampedValueT) {
+        RawValues::<T>::mutate(cid, key, |raw| {
+            if raw.replace(value).is_none() {
+                RawValueCount::<T>::mutate(|count| *count = count.saturating_add(1));
+            }
+        });
+    }
+
+    /// Whether `cid` may feed `key`, i.e. the key is unrestricted or `cid` is on its allowlist.
+    fn is_allowlisted(cid: &CreatorId<T::AccountId>, key: &OracleKeyOf<T>) -> bool {
+        Self::feed_allowlist(key).map_or(true, |(_, allowlist)| allowlist.contains(cid))
+    }
+
+    /// Lift the allowlist of `key` if `creator`'s feed set it.
+    fn remove_allowlist_of(creator: &CreatorId<T::AccountId>, key: &OracleKeyOf<T>) {
+        if Self::feed_allowlist(key).map_or(false, |(owner, _)| owner == *creator) {
+            <FeedAllowlists<T>>::remove(key);
+        }
+    }
+
+    /// Check a fed value against the bounds of the feed owning `key`, whoever feeds it.
+    ///
+    /// Out of bounds values are clamped if `ClampOutOfBounds` is set, and `None` otherwise.
+    fn bound_value(key: &OracleKeyOf<T>, value: i64) -> Option<i64> {
+        let feed = match Self::feed_owners(key).and_then(|owner| Self::api_feeds(owner, key)) {
+            Some(feed) => feed,
+            None => return Some(value),
+        };
+        let mut bounded = value;
+        if let Some(min) = feed.min {
+            bounded = bounded.max(min);
+        }
+        if let Some(max) = feed.max {
+            bounded = bounded.min(max);
+        }
+        (bounded == value || T::ClampOutOfBounds::get()).then(|| bounded)
+    }
+
+    /// Decimal places of the values published for `key`, none for a boolean feed's `1`/`0`.
+    fn decimals_of(key: &OracleKeyOf<T>) -> u8 {
+        let boolean = Self::feed_owners(key).map_or(false, |owner| Self::boolean_feeds(owner, key));
+        if boolean { 0 } else { VALUE_DECIMALS }
+    }
+
+    /// Release the ownership of `key` if `creator`'s feed holds it, along with the owner's pair.
+    fn release_feed_owner(creator: &CreatorId<T::AccountId>, key: &OracleKeyOf<T>) {
+        if Self::feed_owners(key).as_ref() == Some(creator) {
+            <FeedOwners<T>>::remove(key);
+            <ValuePairs<T>>::remove(key);
+        }
+    }
+
+    /// Ensure `cid` may register `key` without exceeding `MaxFeedsPerCreator`.
+    ///
+    /// Replacing an existing feed doesn't count as a new one.
+    fn ensure_feed_capacity(cid: &CreatorId<T::AccountId>, key: &OracleKeyOf<T>) -> DispatchResult {
+        if !ApiFeeds::<T>::contains_key(cid, key) {
+            let count = ApiFeeds::<T>::iter_key_prefix(cid).count() as u32;
+            ensure!(count < T::MaxFeedsPerCreator::get(), Error::<T>::TooManyFeeds);
+        }
+        Ok(())
+    }
+
     pub fn do_remove_api(
         cid: CreatorId<T::AccountId>,
         key: OracleKeyOf<T>,
@@ -854,6 +2569,10 @@ where T::AccountId: AsRef<[u8]>
         if feed_exists {
             let feed = Self::api_feeds(&cid, &key).unwrap();
             <ApiFeeds<T>>::remove(&cid, &key);
+            <BooleanFeeds<T>>::remove(&cid, &key);
+            <FeedHealths<T>>::remove(&cid, &key);
+            Self::remove_allowlist_of(&cid, &key);
+            Self::release_feed_owner(&cid, &key);
             Self::deposit_event(Event::ApiFeedRemoved { sender: cid, key, feed });
             Ok(())
         } else {
@@ -861,4 +2580,40 @@ where T::AccountId: AsRef<[u8]>
         }
     }
 
+    pub fn do_transfer_feed(
+        from: CreatorId<T::AccountId>,
+        key: OracleKeyOf<T>,
+        to: CreatorId<T::AccountId>,
+    ) -> DispatchResult {
+        let feed = Self::api_feeds(&from, &key).ok_or(DispatchError::CannotLookup)?;
+        ensure!(!ApiFeeds::<T>::contains_key(&to, &key), Error::<T>::FeedAlreadyExists);
+        Self::ensure_feed_capacity(&to, &key)?;
+
+        <ApiFeeds<T>>::remove(&from, &key);
+        <ApiFeeds<T>>::insert(&to, &key, feed);
+        if let Some(raw) = <RawValues<T>>::take(&from, &key) {
+            RawValueCount::<T>::mutate(|count| *count = count.saturating_sub(1));
+            Self::insert_raw_value(&to, &key, raw);
+        }
+        if <BooleanFeeds<T>>::take(&from, &key) {
+            <BooleanFeeds<T>>::insert(&to, &key, true);
+        }
+        <FeedHealths<T>>::swap(&from, &key, &to, &key);
+        <FeedAllowlists<T>>::mutate_exists(&key, |allowlist| {
+            if let Some((owner, _)) = allowlist {
+                if *owner == from {
+                    *owner = to.clone();
+                }
+            }
+        });
+        <FeedOwners<T>>::mutate_exists(&key, |owner| {
+            if owner.as_ref() == Some(&from) {
+                *owner = Some(to.clone());
+            }
+        });
+
+        Self::deposit_event(Event::FeedOwnershipTransferred { from, to, key });
+        Ok(())
+    }
+
 }
@@ -0,0 +1,178 @@
+//! Storage migrations of the kylin-oracle pallet.
+
+use crate::{
+    ApiFeed, ApiFeedOf, ApiFeeds, Config, FeedHealths, FeedOwners, Pallet, RawValueCount, RawValues,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+    log,
+    traits::{Get, GetStorageVersion, StorageVersion},
+    weights::Weight,
+};
+use hex::ToHex;
+use sp_runtime::RuntimeDebug;
+use sp_std::{convert::TryInto, vec::Vec};
+
+/// Bounded `ApiFeed` with optional value bounds.
+pub mod v1 {
+    use super::*;
+
+    /// `ApiFeed` as stored before version 1.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub struct OldApiFeed<BlockNumber> {
+        pub requested_block_number: BlockNumber,
+        pub url: Option<Vec<u8>>,
+        pub vpath: Option<Vec<u8>>,
+    }
+
+    /// Translate every `ApiFeeds` entry to `ApiFeedOf<T>`, without value bounds.
+    ///
+    /// Feeds whose URL or value path exceed `MaxUrlLen` or `MaxVPathLen` are removed.
+    pub fn migrate<T: Config>() -> Weight
+    where
+        T::AccountId: AsRef<[u8]> + ToHex,
+    {
+        if Pallet::<T>::on_chain_storage_version() >= 1 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let mut translated = 0u64;
+        let mut removed = 0u64;
+        ApiFeeds::<T>::translate::<OldApiFeed<T::BlockNumber>, _>(|_, _, old| {
+            translated += 1;
+            let feed: Option<ApiFeedOf<T>> = (|| {
+                Some(ApiFeed {
+                    requested_block_number: old.requested_block_number,
+                    url: old.url.map(|url| url.try_into()).transpose().ok()?,
+                    vpath: old.vpath.map(|vpath| vpath.try_into()).transpose().ok()?,
+                    min: None,
+                    max: None,
+                    disabled: false,
+                })
+            })();
+            if feed.is_none() {
+                removed += 1;
+            }
+            feed
+        });
+        StorageVersion::new(1).put::<Pallet<T>>();
+        log::info!("Migrated {} oracle feeds to v1, removed {} over-length ones", translated, removed);
+
+        T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+    }
+}
+
+/// `ApiFeed` with a flag disabling feeds that keep failing.
+pub mod v2 {
+    use super::*;
+    use frame_support::BoundedVec;
+
+    /// `ApiFeed` as stored in version 1.
+    #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+    pub struct OldApiFeed<BlockNumber, Url, VPath> {
+        pub requested_block_number: BlockNumber,
+        pub url: Option<Url>,
+        pub vpath: Option<VPath>,
+        pub min: Option<i64>,
+        pub max: Option<i64>,
+    }
+
+    type OldApiFeedOf<T> = OldApiFeed<
+        <T as frame_system::Config>::BlockNumber,
+        BoundedVec<u8, <T as Config>::MaxUrlLen>,
+        BoundedVec<u8, <T as Config>::MaxVPathLen>,
+    >;
+
+    /// Translate every `ApiFeeds` entry to `ApiFeedOf<T>`, all feeds enabled.
+    pub fn migrate<T: Config>() -> Weight
+    where
+        T::AccountId: AsRef<[u8]> + ToHex,
+    {
+        if Pallet::<T>::on_chain_storage_version() >= 2 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let mut translated = 0u64;
+        ApiFeeds::<T>::translate::<OldApiFeedOf<T>, _>(|_, _, old| {
+            translated += 1;
+            Some(ApiFeed {
+                requested_block_number: old.requested_block_number,
+                url: old.url,
+                vpath: old.vpath,
+                min: old.min,
+                max: old.max,
+                disabled: false,
+            })
+        });
+        StorageVersion::new(2).put::<Pallet<T>>();
+        log::info!("Migrated {} oracle feeds to v2", translated);
+
+        T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+    }
+}
+
+/// `FeedOwners` index of the creator whose feed bounds each key.
+pub mod v3 {
+    use super::*;
+
+    /// Record a creator of every registered key as its owner, the first one in storage order.
+    pub fn migrate<T: Config>() -> Weight
+    where
+        T::AccountId: AsRef<[u8]> + ToHex,
+    {
+        if Pallet::<T>::on_chain_storage_version() >= 3 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let mut feeds = 0u64;
+        let mut owned = 0u64;
+        for (creator, key) in ApiFeeds::<T>::iter_keys() {
+            feeds += 1;
+            if !FeedOwners::<T>::contains_key(&key) {
+                FeedOwners::<T>::insert(&key, creator);
+                owned += 1;
+            }
+        }
+        StorageVersion::new(3).put::<Pallet<T>>();
+        log::info!("Recorded owners of {} oracle keys from {} feeds", owned, feeds);
+
+        T::DbWeight::get().reads_writes(2 * feeds + 1, owned + 1)
+    }
+}
+
+/// `RawValueCount` of the raw values stored so far.
+pub mod v4 {
+    use super::*;
+
+    /// Count the entries of `RawValues`.
+    pub fn migrate<T: Config>() -> Weight {
+        if Pallet::<T>::on_chain_storage_version() >= 4 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let count = RawValues::<T>::iter_keys().count() as u32;
+        RawValueCount::<T>::put(count);
+        StorageVersion::new(4).put::<Pallet<T>>();
+        log::info!("Counted {} oracle raw values", count);
+
+        T::DbWeight::get().reads_writes(count as u64 + 1, 2)
+    }
+}
+
+/// `FeedHealths` keyed by the feed's creator rather than the reporting operator.
+pub mod v5 {
+    use super::*;
+
+    /// Clear the fetch health recorded per reporting operator, it's rebuilt by the next reports.
+    pub fn migrate<T: Config>() -> Weight {
+        if Pallet::<T>::on_chain_storage_version() >= 5 {
+            return T::DbWeight::get().reads(1);
+        }
+
+        let removed = FeedHealths::<T>::clear(u32::MAX, None).unique;
+        StorageVersion::new(5).put::<Pallet<T>>();
+        log::info!("Cleared {} oracle fetch health entries", removed);
+
+        T::DbWeight::get().reads_writes(removed as u64 + 1, removed as u64 + 1)
+    }
+}
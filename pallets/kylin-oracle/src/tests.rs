@@ -1,26 +1,27 @@
 
 use crate as kylin_oracle;
 use crate::*;
-use codec::Decode;
+use codec::{Decode, Encode};
 use frame_support::{
-    parameter_types,
-    traits::Everything,
+    assert_noop, assert_ok, parameter_types,
+    pallet_prelude::{InvalidTransaction, TransactionSource, ValidateUnsigned},
+    traits::{Everything, Hooks},
     weights::{IdentityFee, Weight, ConstantMultiplier},
 };
 
 use sp_core::{
-    offchain::{testing, OffchainWorkerExt, TransactionPoolExt},
+    offchain::{testing, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt},
     sr25519::Signature,
     H256,
 };
 use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
-use sp_std::str;
 use sp_std::vec::Vec;
 use std::sync::Arc;
 
 use sp_runtime::{
     testing::{Header, TestXt},
     traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Verify},
+    Perbill, Permill,
 };
 use xcm_builder::{
     AllowUnpaidExecutionFrom, EnsureXcmOrigin, FixedWeightBounds, SignedToAccountId32,
@@ -48,7 +49,7 @@ frame_support::construct_runtime!(
         Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         KylinOracle: kylin_oracle::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
         Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
-        TransactionPayment: pallet_transaction_payment::{Pallet, Storage},
+        TransactionPayment: pallet_transaction_payment::{Pallet, Storage, Event<T>},
         XcmPallet: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin} = 51,
         CumulusXcm: cumulus_pallet_xcm::{Pallet, Call, Event<T>, Origin} = 52,
 
@@ -62,6 +63,7 @@ parameter_types! {
 pub type Balance = u64;
 
 impl pallet_transaction_payment::Config for Test {
+    type RuntimeEvent = RuntimeEvent;
     type OnChargeTransaction = pallet_transaction_payment::CurrencyAdapter<Balances, ()>;
     type OperationalFeeMultiplier = OperationalFeeMultiplier;
     type LengthToFee = ConstantMultiplier<Balance, TransactionByteFee>;
@@ -75,7 +77,7 @@ parameter_types! {
         frame_system::limits::BlockWeights::simple_max(1024);
     pub Ancestry: MultiLocation = Here.into();
 }
-pub type LocalOriginToLocation = SignedToAccountId32<Origin, AccountId, RelayNetwork>;
+pub type LocalOriginToLocation = SignedToAccountId32<RuntimeOrigin, AccountId, RelayNetwork>;
 pub type Barrier = AllowUnpaidExecutionFrom<Everything>;
 
 impl frame_system::Config for Test {
@@ -83,8 +85,8 @@ impl frame_system::Config for Test {
     type BlockWeights = ();
     type BlockLength = ();
     type DbWeight = ();
-    type Origin = Origin;
-    type Call = Call;
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
     type Index = u64;
     type BlockNumber = u64;
     type Hash = H256;
@@ -92,7 +94,7 @@ impl frame_system::Config for Test {
     type AccountId = sp_core::sr25519::Public;
     type Lookup = IdentityLookup<Self::AccountId>;
     type Header = Header;
-    type Event = Event;
+    type RuntimeEvent = RuntimeEvent;
     type BlockHashCount = BlockHashCount;
     type Version = ();
     type PalletInfo = PalletInfo;
@@ -105,7 +107,7 @@ impl frame_system::Config for Test {
     type MaxConsumers = frame_support::traits::ConstU32<16>;
 }
 
-type Extrinsic = TestXt<Call, ()>;
+type Extrinsic = TestXt<RuntimeCall, ()>;
 type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
 
 impl frame_system::offchain::SigningTypes for Test {
@@ -115,9 +117,9 @@ impl frame_system::offchain::SigningTypes for Test {
 
 impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
 where
-    Call: From<LocalCall>,
+    RuntimeCall: From<LocalCall>,
 {
-    type OverarchingCall = Call;
+    type OverarchingCall = RuntimeCall;
     type Extrinsic = Extrinsic;
 }
 
@@ -134,7 +136,7 @@ parameter_types! {
 
 impl pallet_balances::Config for Test {
     type Balance = u64;
-    type Event = Event;
+    type RuntimeEvent = RuntimeEvent;
     type DustRemoval = ();
     type ExistentialDeposit = ExistentialDeposit;
     type AccountStore = System;
@@ -149,6 +151,25 @@ impl SendXcm for DoNothingRouter {
         Ok(())
     }
 }
+
+parameter_types! {
+    pub static XcmSendFails: bool = false;
+    pub static SentXcm: Vec<Xcm<()>> = vec![];
+}
+
+pub struct MockXcmSender;
+impl SendXcm for MockXcmSender {
+    fn send_xcm(_dest: impl Into<MultiLocation>, msg: Xcm<()>) -> SendResult {
+        if XcmSendFails::get() {
+            Err(SendError::Transport("mock channel is down"))
+        } else {
+            let mut sent = SentXcm::get();
+            sent.push(msg);
+            SentXcm::set(sent);
+            Ok(())
+        }
+    }
+}
 // For testing the module, we construct a mock runtime.
 
 parameter_types! {
@@ -164,14 +185,14 @@ impl pallet_timestamp::Config for Test {
 // pub trait Config: CreateSignedTransaction<Self> + frame_system::Config {}
 impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
 where
-    Call: From<LocalCall>,
+    RuntimeCall: From<LocalCall>,
 {
     fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
-        call: Call,
+        call: RuntimeCall,
         _public: <Signature as Verify>::Signer,
         _account: AccountId,
         nonce: u64,
-    ) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+    ) -> Option<(RuntimeCall, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
         Some((call, (nonce, ())))
     }
 }
@@ -181,17 +202,132 @@ parameter_types! {
     pub const RelayNetwork: NetworkId = NetworkId::Kusama;
     // pub RelayChainOrigin: Origin = cumulus_pallet_xcm::Origin::Relay.into();
 }
+
+pub struct OracleMembers;
+impl SortedMembers<AccountId> for OracleMembers {
+    fn sorted_members() -> Vec<AccountId> {
+        let mut members = vec![alice(), bob(), charlie()];
+        members.retain(|member| Some(member) != RemovedMember::get().as_ref());
+        members.sort();
+        members
+    }
+}
+
+parameter_types! {
+    pub static MinimumCount: u32 = 1;
+    pub const ExpiresIn: u128 = 600_000;
+    pub static LatestWins: bool = false;
+    pub static MeanWins: bool = false;
+    pub static SignWithAllAccounts: bool = false;
+    pub static FeedSubmissionMode: SubmissionMode = SubmissionMode::SignedOnly;
+    pub static UpdateDeadband: u64 = 0;
+    pub static ClampOutOfBounds: bool = false;
+    pub static StrictAllOperators: bool = false;
+    pub static FrozenRounds: u32 = 0;
+    pub static SuppressFrozen: bool = false;
+    pub static RemovedMember: Option<AccountId> = None;
+    pub static MaxHasDispatchedSize: u32 = 100;
+    pub static MaxConsecutiveFailures: u32 = 0;
+    pub static FetchFailureQuorum: u32 = 1;
+    pub static EmitDispersion: bool = false;
+    pub static StoreConfidence: bool = false;
+    pub static MaxConcurrentFetches: u32 = 1;
+    pub static FeedbackPalletIndex: u8 = 168;
+    pub static FeedbackCallIndex: u8 = 7;
+}
+
+/// Median by default, or the most recent report when `LatestWins` is set.
+pub struct MockCombineData;
+impl CombineData<OracleKeyOf<Test>, TimestampedValueT> for MockCombineData {
+    fn combine_data(
+        key: &OracleKeyOf<Test>,
+        values: Vec<TimestampedValueT>,
+        prev_value: Option<TimestampedValueT>,
+    ) -> Option<TimestampedValueT> {
+        if LatestWins::get() {
+            values.into_iter().max_by_key(|x| x.timestamp).or(prev_value)
+        } else if MeanWins::get() {
+            CombineStrategy::Mean.combine(values, 0).or(prev_value)
+        } else {
+            DefaultCombineData::<Test, MinimumCount, ExpiresIn>::combine_data(key, values, prev_value)
+        }
+    }
+}
+
+impl CombineKind for MockCombineData {
+    fn is_averaging() -> bool {
+        MeanWins::get()
+    }
+}
+
 impl kylin_oracle::Config for Test {
-    type Event = Event;
+    type RuntimeEvent = RuntimeEvent;
     type AuthorityId = crypto::TestAuthId;
-    type Call = Call;
-    type Origin = Origin;
-    type XcmSender = DoNothingRouter;
+    type RuntimeCall = RuntimeCall;
+    type RuntimeOrigin = RuntimeOrigin;
+    type XcmSender = MockXcmSender;
+    type FeedbackPalletIndex = FeedbackPalletIndex;
+    type FeedbackCallIndex = FeedbackCallIndex;
     type UnsignedPriority = UnsignedPriority;
     type UnixTime = pallet_timestamp::Pallet<Test>;
     type Currency = Balances;
     type WeightInfo = ();
     type EstimateCallFee = TransactionPayment;
+    type CombineData = MockCombineData;
+    type Members = OracleMembers;
+    type StrLimit = ConstU32<512>;
+    type MaxUrlLen = ConstU32<64>;
+    type MaxVPathLen = ConstU32<16>;
+    type MaxTagLen = ConstU32<8>;
+    type MaxAllowlistLen = ConstU32<2>;
+    type MaxHasDispatchedSize = MaxHasDispatchedSize;
+    type MaxResponseBytes = ConstU32<64>;
+    type MaxRawValueAge = ConstU128<60_000>;
+    type MaxFeedsPerCreator = ConstU32<3>;
+    type MaxPendingResponses = ConstU32<10>;
+    type MaxResponseRetries = ConstU32<3>;
+    type OffchainFetchBudget = ConstU64<0>;
+    type MaxConcurrentFetches = MaxConcurrentFetches;
+    type SignWithAllAccounts = SignWithAllAccounts;
+    type SubmissionMode = FeedSubmissionMode;
+    type MaxFeedNonces = ConstU32<4>;
+    type AdminOrigin = frame_system::EnsureRoot<AccountId>;
+    type MaxSourceGroups = ConstU32<2>;
+    type MaxSourceGroupSize = ConstU32<2>;
+    type FetchInterval = ConstU32<1>;
+    type UpdateDeadband = UpdateDeadband;
+    type ClampOutOfBounds = ClampOutOfBounds;
+    type StrictAllOperators = StrictAllOperators;
+    type OnNewValue = RecordNewValues;
+    type FrozenRounds = FrozenRounds;
+    type SuppressFrozen = SuppressFrozen;
+    type ValueSink = RecordPublishedValues;
+    type MaxConsecutiveFailures = MaxConsecutiveFailures;
+    type FetchFailureQuorum = FetchFailureQuorum;
+    type EmitDispersion = EmitDispersion;
+    type StoreConfidence = StoreConfidence;
+}
+
+parameter_types! {
+    pub static NewValues: Vec<(CreatorId<AccountId>, Vec<u8>, i64)> = vec![];
+}
+
+pub struct RecordNewValues;
+impl OnNewData<CreatorId<AccountId>, OracleKeyOf<Test>, i64> for RecordNewValues {
+    fn on_new_data(who: &CreatorId<AccountId>, key: &OracleKeyOf<Test>, value: &i64) {
+        NewValues::mutate(|values| values.push((who.clone(), key.to_vec(), *value)));
+    }
+}
+
+parameter_types! {
+    pub static PublishedValues: Vec<(Vec<u8>, i64, u128)> = vec![];
+}
+
+pub struct RecordPublishedValues;
+impl ValueSink<OracleKeyOf<Test>> for RecordPublishedValues {
+    fn on_value_published(key: &OracleKeyOf<Test>, value: i64, timestamp: u128) {
+        PublishedValues::mutate(|values| values.push((key.to_vec(), value, timestamp)));
+    }
 }
 
 parameter_types! {
@@ -222,15 +358,15 @@ impl TransactAsset for DummyAssetTransactor {
 }
 pub struct XcmConfig;
 impl xcm_executor::Config for XcmConfig {
-    type Call = Call;
+    type RuntimeCall = RuntimeCall;
     type XcmSender = DoNothingRouter;
     type AssetTransactor = DummyAssetTransactor;
-    type OriginConverter = pallet_xcm::XcmPassthrough<Origin>;
+    type OriginConverter = pallet_xcm::XcmPassthrough<RuntimeOrigin>;
     type IsReserve = ();
     type IsTeleporter = ();
     type LocationInverter = LocationInverter<Ancestry>;
     type Barrier = Barrier;
-    type Weigher = FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>;
+    type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
     type Trader = DummyWeightTrader;
     type ResponseHandler = ();
     type AssetTrap = XcmPallet;
@@ -243,393 +379,2564 @@ parameter_types! {
 }
 
 impl pallet_xcm::Config for Test {
-    type Event = Event;
-    type SendXcmOrigin = EnsureXcmOrigin<Origin, LocalOriginToLocation>;
+    type RuntimeEvent = RuntimeEvent;
+    type SendXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
     type XcmRouter = DoNothingRouter;
     type LocationInverter = LocationInverter<Ancestry>;
-    type ExecuteXcmOrigin = EnsureXcmOrigin<Origin, LocalOriginToLocation>;
+    type ExecuteXcmOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
     type XcmExecuteFilter = Everything;
     type XcmExecutor = XcmExecutor<XcmConfig>;
     type XcmTeleportFilter = Everything;
-    type Weigher = FixedWeightBounds<UnitWeightCost, Call, MaxInstructions>;
+    type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
     type XcmReserveTransferFilter = Everything;
-    type Origin = Origin;
-    type Call = Call;
+    type RuntimeOrigin = RuntimeOrigin;
+    type RuntimeCall = RuntimeCall;
     const VERSION_DISCOVERY_QUEUE_SIZE: u32 = 100;
     type AdvertisedXcmVersion = AdvertisedXcmVersion;
 }
 
 impl cumulus_pallet_xcm::Config for Test {
-    type Event = Event;
+    type RuntimeEvent = RuntimeEvent;
     type XcmExecutor = XcmExecutor<XcmConfig>;
 }
 
-#[test]
-fn should_save_data_onchain_for_signed_data_submissions() {
-    const PHRASE: &str =
-        "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
-    let (offchain, offchain_state) = testing::TestOffchainExt::new();
-    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
-
-    let keystore = KeyStore::new();
+fn alice() -> AccountId {
+    get_account_id_from_seed::<sr25519::Public>("Alice")
+}
 
-    SyncCryptoStore::sr25519_generate_new(
-        &keystore,
-        kylin_oracle::KEY_TYPE,
-        Some(&format!("{}/hunter1", PHRASE)),
-    )
-    .unwrap();
-    let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+fn bob() -> AccountId {
+    get_account_id_from_seed::<sr25519::Public>("Bob")
+}
 
-    let mut t = sp_io::TestExternalities::default();
-    t.register_extension(OffchainWorkerExt::new(offchain));
-    t.register_extension(TransactionPoolExt::new(pool));
-    t.register_extension(KeystoreExt(Arc::new(keystore)));
-    mock_submit_response(&mut offchain_state.write());
-    let expected_response = br#"{"USD": 155.23}"#.to_vec();
-    t.execute_with(|| {
-        KylinOracle::submit_price_feed(
-            Origin::signed(alice),
-            None,
-            str::from_utf8(b"btc_usd").unwrap().as_bytes().to_vec(),
-        )
-        .unwrap();
-        KylinOracle::fetch_data_and_send_signed(1).unwrap();
+fn charlie() -> AccountId {
+    get_account_id_from_seed::<sr25519::Public>("Charlie")
+}
 
-        let tx1 = pool_state.write().transactions.pop().unwrap();
-        let tx2 = pool_state.write().transactions.pop().unwrap();
+fn key(name: &[u8]) -> OracleKeyOf<Test> {
+    name.to_vec().try_into().unwrap()
+}
 
-        let tx1 = Extrinsic::decode(&mut &*tx1).unwrap();
-        let tx2 = Extrinsic::decode(&mut &*tx2).unwrap();
+fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t: sp_io::TestExternalities = frame_system::GenesisConfig::default()
+        .build_storage::<Test>()
+        .unwrap()
+        .into();
+    t.execute_with(|| System::set_block_number(1));
+    t
+}
 
-        if let Call::KylinOracle(crate::Call::submit_data_signed {
-            block_number: _block_number,
-            key: _key,
-            data: response,
-        }) = tx2.call
-        {
-            assert_eq!(response, expected_response);
-        }
+fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
+    TPublic::Pair::from_string(&format!("//{}", seed), None)
+        .expect("static values are valid; qed")
+        .public()
+}
 
-        if let Call::KylinOracle(crate::Call::clear_processed_requests_unsigned {
-            block_number: _block_number,
-            processed_requests,
-        }) = tx1.call
-        {
-            assert_eq!(1, processed_requests.len());
-        }
-    });
+fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId
+where
+    AccountPublic: From<<TPublic::Pair as Pair>::Public>,
+{
+    AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
 #[test]
-fn should_save_data_onchain_for_unsigned_submissions() {
-    // const PHRASE: &str =
-    // "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
-    let (offchain, offchain_state) = testing::TestOffchainExt::new();
-    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+fn feed_lags_reports_staleness_sorted_by_key() {
+    new_test_ext().execute_with(|| {
+        Values::<Test>::insert(key(b"eth_usd"), TimestampedValue { value: 1, timestamp: 1_000 });
+        Values::<Test>::insert(key(b"btc_usd"), TimestampedValue { value: 2, timestamp: 4_000 });
+        Values::<Test>::insert(key(b"dot_usd"), TimestampedValue { value: 3, timestamp: 10_000 });
 
-    let keystore = KeyStore::new();
-    let mut t = sp_io::TestExternalities::default();
-    t.register_extension(OffchainWorkerExt::new(offchain));
-    t.register_extension(TransactionPoolExt::new(pool));
-    t.register_extension(KeystoreExt(Arc::new(keystore)));
-    let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        assert_eq!(
+            KylinOracle::feed_lags(10_000),
+            vec![
+                (b"btc_usd".to_vec(), 6_000),
+                (b"dot_usd".to_vec(), 0),
+                (b"eth_usd".to_vec(), 9_000),
+            ]
+        );
+    });
+}
 
-    mock_submit_response(&mut offchain_state.write());
-    let expected_response = br#"{"USD": 155.23}"#.to_vec();
-    t.execute_with(|| {
-        KylinOracle::submit_price_feed(
-            Origin::signed(alice),
+#[test]
+fn transfer_feed_moves_feed_to_new_creator() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"btc_usd"),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
             None,
-            str::from_utf8(b"btc_usd").unwrap().as_bytes().to_vec(),
-        )
-        .unwrap();
-        KylinOracle::fetch_data_and_send_raw_unsigned(1).unwrap();
-
-        let tx1 = pool_state.write().transactions.pop().unwrap();
-        let tx2 = pool_state.write().transactions.pop().unwrap();
-
-        let tx1 = Extrinsic::decode(&mut &*tx1).unwrap();
-        let tx2 = Extrinsic::decode(&mut &*tx2).unwrap();
+            None,
+            None,
+            None,
+        ));
+        RawValues::<Test>::insert(
+            CreatorId::AccountId(alice()),
+            key(b"btc_usd"),
+            TimestampedValue { value: 42, timestamp: 1_000 },
+        );
+        assert_ok!(KylinOracle::report_fetch_errors(
+            RuntimeOrigin::signed(charlie()),
+            vec![(CreatorId::AccountId(alice()), key(b"btc_usd"), FetchErrorKind::Http)],
+        ));
+
+        assert_ok!(KylinOracle::transfer_feed(RuntimeOrigin::signed(alice()), key(b"btc_usd"), bob()));
+
+        assert!(KylinOracle::api_feeds(CreatorId::AccountId(alice()), key(b"btc_usd")).is_none());
+        let feed = KylinOracle::api_feeds(CreatorId::AccountId(bob()), key(b"btc_usd")).unwrap();
+        assert_eq!(feed.url.map(|url| url.into_inner()), Some(b"https://api.kylin-node.co.uk/prices".to_vec()));
+        assert_eq!(feed.vpath.map(|vpath| vpath.into_inner()), Some(b"/USD".to_vec()));
+        assert!(KylinOracle::raw_values(CreatorId::AccountId(alice()), key(b"btc_usd")).is_none());
+        assert_eq!(KylinOracle::feed_owners(key(b"btc_usd")), Some(CreatorId::AccountId(bob())));
+        assert_eq!(
+            KylinOracle::raw_values(CreatorId::AccountId(bob()), key(b"btc_usd")).map(|v| v.value),
+            Some(42)
+        );
+        assert!(!FeedHealths::<Test>::contains_key(CreatorId::AccountId(alice()), key(b"btc_usd")));
+        assert_eq!(
+            KylinOracle::feed_health(CreatorId::AccountId(bob()), key(b"btc_usd")).reporters,
+            vec![charlie()]
+        );
+    });
+}
 
-        if let Call::KylinOracle(crate::Call::submit_data_unsigned {
-            block_number: _block_number,
-            key: _key,
-            data: response,
-        }) = tx2.call
-        {
-            assert_eq!(response, expected_response);
-        }
+#[test]
+fn transfer_feed_rejects_non_owner() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"btc_usd"),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
 
-        if let Call::KylinOracle(crate::Call::clear_processed_requests_unsigned {
-            block_number: _block_number,
-            processed_requests,
-        }) = tx1.call
-        {
-            assert_eq!(1, processed_requests.len());
-        }
+        assert_noop!(
+            KylinOracle::transfer_feed(RuntimeOrigin::signed(bob()), key(b"btc_usd"), charlie()),
+            DispatchError::CannotLookup
+        );
+        assert!(KylinOracle::api_feeds(CreatorId::AccountId(alice()), key(b"btc_usd")).is_some());
     });
 }
 
 #[test]
-fn should_write_data_onchain_directly_for_signed_requests() {
-    const PHRASE: &str =
-        "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
-    let (offchain, _offchain_state) = testing::TestOffchainExt::new();
-    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
-    let keystore = KeyStore::new();
-    SyncCryptoStore::sr25519_generate_new(
-        &keystore,
-        kylin_oracle::KEY_TYPE,
-        Some(&format!("{}/hunter1", PHRASE)),
-    )
-    .unwrap();
+fn median_is_independent_of_report_order() {
+    let combine = |reports: Vec<(AccountId, TimestampedValueT)>| {
+        new_test_ext().execute_with(|| {
+            Timestamp::set_timestamp(10_000);
+            for (who, value) in reports {
+                RawValues::<Test>::insert(CreatorId::AccountId(who), key(b"btc_usd"), value);
+            }
+            KylinOracle::combined(&key(b"btc_usd"))
+        })
+    };
+    let at = |timestamp| TimestampedValue { value: 100, timestamp };
 
+    let expected = Some(at(2_000));
+    assert_eq!(combine(vec![(alice(), at(3_000)), (bob(), at(1_000)), (charlie(), at(2_000))]), expected);
+    assert_eq!(combine(vec![(charlie(), at(1_000)), (alice(), at(2_000)), (bob(), at(3_000))]), expected);
+    assert_eq!(combine(vec![(bob(), at(2_000)), (charlie(), at(3_000)), (alice(), at(1_000))]), expected);
+}
+
+fn offchain_test_ext(uri: &str, response: Vec<u8>) -> sp_io::TestExternalities {
+    let (offchain, offchain_state) = testing::TestOffchainExt::new();
+    offchain_state.write().expect_request(testing::PendingRequest {
+        method: "GET".into(),
+        uri: uri.into(),
+        response: Some(response),
+        sent: true,
+        ..Default::default()
+    });
     let mut t = sp_io::TestExternalities::default();
     t.register_extension(OffchainWorkerExt::new(offchain));
-    t.register_extension(TransactionPoolExt::new(pool));
-    t.register_extension(KeystoreExt(Arc::new(keystore)));
-    let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
-
-    let feed_name = b"test_feed".to_vec();
-    let sample_data = b"{sample_data}".to_vec();
-    t.execute_with(|| {
-        KylinOracle::write_data_onchain(
-            Origin::signed(alice),
-            feed_name,
-            sample_data.clone(),
-        )
-        .unwrap();
-        KylinOracle::fetch_data_and_send_signed(1).unwrap();
-
-        let tx1 = pool_state.write().transactions.pop().unwrap();
-        let tx2 = pool_state.write().transactions.pop().unwrap();
-
-        let tx1 = Extrinsic::decode(&mut &*tx1).unwrap();
-        let tx2 = Extrinsic::decode(&mut &*tx2).unwrap();
+    t
+}
 
-        if let Call::KylinOracle(crate::Call::submit_data_signed {
-            block_number: _block_number,
-            key: _key,
-            data: response,
-        }) = tx2.call
-        {
-            assert_eq!(response, sample_data);
-        }
+#[test]
+fn fetch_http_get_results_reads_body_within_limit() {
+    let uri = "https://api.kylin-node.co.uk/prices";
+    offchain_test_ext(uri, br#"{"USD": 155.23}"#.to_vec()).execute_with(|| {
+        assert_eq!(
+            KylinOracle::fetch_http_get_results(&[uri.as_bytes()], 64),
+            vec![Ok(br#"{"USD": 155.23}"#.to_vec())]
+        );
+    });
+}
 
-        if let Call::KylinOracle(crate::Call::clear_processed_requests_unsigned {
-            block_number: _block_number,
-            processed_requests,
-        }) = tx1.call
-        {
-            assert_eq!(1, processed_requests.len());
-        }
+#[test]
+fn fetch_http_get_results_aborts_on_oversized_body() {
+    let uri = "https://api.kylin-node.co.uk/prices";
+    offchain_test_ext(uri, vec![b'1'; 65]).execute_with(|| {
+        assert_eq!(
+            KylinOracle::fetch_http_get_results(&[uri.as_bytes()], 64),
+            vec![Err(http::Error::Unknown)]
+        );
     });
 }
 
-fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
-    TPublic::Pair::from_string(&format!("//{}", seed), None)
-        .expect("static values are valid; qed")
-        .public()
+#[test]
+fn fetch_http_get_results_returns_non_utf8_body_as_is() {
+    let uri = "https://api.kylin-node.co.uk/prices";
+    let body = [&br#"{"USD": 155.23}"#[..], &[0xff, 0xfe]].concat();
+    offchain_test_ext(uri, body.clone()).execute_with(|| {
+        assert_eq!(KylinOracle::fetch_http_get_results(&[uri.as_bytes()], 64), vec![Ok(body)]);
+    });
 }
 
-fn get_account_id_from_seed<TPublic: Public>(seed: &str) -> AccountId
-where
-    AccountPublic: From<<TPublic::Pair as Pair>::Public>,
-{
-    AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
+#[test]
+fn fetch_http_get_results_rejects_declared_length_over_budget() {
+    let uri = "https://api.kylin-node.co.uk/prices";
+    let (offchain, offchain_state) = testing::TestOffchainExt::new();
+    for _ in 0..2 {
+        offchain_state.write().expect_request(testing::PendingRequest {
+            method: "GET".into(),
+            uri: uri.into(),
+            response: Some(br#"{"USD": 155.23}"#.to_vec()),
+            response_headers: vec![("content-length".into(), "15".into())],
+            sent: true,
+            ..Default::default()
+        });
+    }
+    let mut t = sp_io::TestExternalities::default();
+    t.register_extension(OffchainWorkerExt::new(offchain));
+    t.execute_with(|| {
+        assert_eq!(
+            KylinOracle::fetch_http_get_results(&[uri.as_bytes()], 8),
+            vec![Err(http::Error::Unknown)]
+        );
+        assert_eq!(
+            KylinOracle::fetch_http_get_results(&[uri.as_bytes()], 15),
+            vec![Ok(br#"{"USD": 155.23}"#.to_vec())]
+        );
+    });
 }
 
+#[test]
+fn query_response_fee_matches_transaction_payment() {
+    new_test_ext().execute_with(|| {
+        let call = crate::Call::<Test>::xcm_query_data { key: key(b"btc_usd") };
+        let expected = TransactionPayment::compute_fee(
+            call.encode().len() as u32,
+            &call.get_dispatch_info(),
+            0,
+        );
 
-fn mock_submit_response(state: &mut testing::OffchainState) {
-    state.expect_request(testing::PendingRequest {
-        method: "GET".into(),
-        uri: "https://api.kylin-node.co.uk/prices?currency_pairs=btc_usd".into(),
-        response: Some(br#"{"USD": 155.23}"#.to_vec()),
-        sent: true,
-        ..Default::default()
+        let fee = KylinOracle::query_response_fee(key(b"btc_usd"));
+        assert!(fee > 0);
+        assert_eq!(fee, expected);
     });
+}
+
+#[test]
+fn combine_ignores_stale_raw_values() {
+    new_test_ext().execute_with(|| {
+        Timestamp::set_timestamp(100_000);
+        RawValues::<Test>::insert(
+            CreatorId::AccountId(alice()),
+            key(b"btc_usd"),
+            TimestampedValue { value: 100, timestamp: 10_000 },
+        );
+        RawValues::<Test>::insert(
+            CreatorId::AccountId(bob()),
+            key(b"btc_usd"),
+            TimestampedValue { value: 200, timestamp: 90_000 },
+        );
 
+        assert_eq!(
+            KylinOracle::combined(&key(b"btc_usd")),
+            Some(TimestampedValue { value: 200, timestamp: 90_000 })
+        );
+    });
 }
 
-fn mock_post_response(state: &mut testing::OffchainState) {
-    
+#[test]
+fn combine_is_suppressed_when_too_few_fresh_values() {
+    new_test_ext().execute_with(|| {
+        MinimumCount::set(2);
+        Timestamp::set_timestamp(100_000);
+        Values::<Test>::insert(key(b"btc_usd"), TimestampedValue { value: 150, timestamp: 5_000 });
+        RawValues::<Test>::insert(
+            CreatorId::AccountId(alice()),
+            key(b"btc_usd"),
+            TimestampedValue { value: 100, timestamp: 10_000 },
+        );
+        RawValues::<Test>::insert(
+            CreatorId::AccountId(bob()),
+            key(b"btc_usd"),
+            TimestampedValue { value: 200, timestamp: 90_000 },
+        );
 
-    // state.expect_request(testing::PendingRequest {
-    //     method: "GET".into(),
-    //     uri: "https://api.kylin-node.co.uk/prices?currency_pairs=btc_usd".into(),
-    //     response: Some(br#"{"USD": 155.23}"#.to_vec()),
-    //     sent: true,
-    //     ..Default::default()
-    // });
+        assert_eq!(KylinOracle::combined(&key(b"btc_usd")), None);
+    });
+}
 
+fn api_feed(name: &[u8]) -> (OracleKeyOf<Test>, Vec<u8>, Vec<u8>) {
+    (key(name), b"https://api.kylin-node.co.uk/prices".to_vec(), b"/USD".to_vec())
+}
 
-    let sample_body:Vec<u8> =  vec![123, 10, 32, 32, 32, 32, 34, 100, 97, 116, 97, 34, 58, 32, 123, 10, 32, 32, 32, 32, 32, 32, 32, 32, 34, 112, 97, 114, 97, 95, 105, 100, 34, 58, 32, 110, 117, 108, 108, 44, 10, 32, 32, 32, 32, 32, 32, 32, 32, 34, 97, 99, 99, 111, 117, 110, 116, 95, 105, 100, 34, 58, 32, 34, 100, 52, 51, 53, 57, 51, 99, 55, 49, 53, 102, 100, 100, 51, 49, 99, 54, 49, 49, 52, 49, 97, 98, 100, 48, 52, 97, 57, 57, 102, 100, 54, 56, 50, 50, 99, 56, 53, 53, 56, 56, 53, 52, 99, 99, 100, 101, 51, 57, 97, 53, 54, 56, 52, 101, 55, 97, 53, 54, 100, 97, 50, 55, 100, 34, 44, 10, 32, 32, 32, 32, 32, 32, 32, 32, 34, 114, 101, 113, 117, 101, 115, 116, 101, 100, 95, 98, 108, 111, 99, 107, 95, 110, 117, 109, 98, 101, 114, 34, 58, 32, 48, 44, 10, 32, 32, 32, 32, 32, 32, 32, 32, 34, 112, 114, 111, 99, 101, 115, 115, 101, 100, 95, 98, 108, 111, 99, 107, 95, 110, 117, 109, 98, 101, 114, 34, 58, 32, 48, 44, 10, 32, 32, 32, 32, 32, 32, 32, 32, 34, 114, 101, 113, 117, 101, 115, 116, 101, 100, 95, 116, 105, 109, 101, 115, 116, 97, 109, 112, 34, 58, 32, 48, 44, 10, 32, 32, 32, 32, 32, 32, 32, 32, 34, 112, 114, 111, 99, 101, 115, 115, 101, 100, 95, 116, 105, 109, 101, 115, 116, 97, 109, 112, 34, 58, 32, 48, 44, 10, 32, 32, 32, 32, 32, 32, 32, 32, 34, 112, 97, 121, 108, 111, 97, 100, 34, 58, 32, 34, 123, 115, 97, 109, 112, 108, 101, 95, 100, 97, 116, 97, 125, 34, 44, 10, 32, 32, 32, 32, 32, 32, 32, 32, 34, 102, 101, 101, 100, 95, 110, 97, 109, 101, 34, 58, 32, 34, 112, 114, 105, 99, 101, 95, 102, 101, 101, 100, 105, 110, 103, 34, 44, 10, 32, 32, 32, 32, 32, 32, 32, 32, 34, 117, 114, 108, 34, 58, 32, 34, 104, 116, 116, 112, 115, 58, 47, 47, 97, 112, 105, 46, 107, 121, 108, 105, 110, 45, 110, 111, 100, 101, 46, 99, 111, 46, 117, 107, 47, 112, 114, 105, 99, 101, 115, 63, 99, 117, 114, 114, 101, 110, 99, 121, 95, 112, 97, 105, 114, 115, 61, 98, 116, 99, 95, 117, 115, 100, 34, 10, 32, 32, 32, 32, 125, 44, 10, 32, 32, 32, 32, 34, 104, 97, 115, 104, 34, 58, 32, 34, 57, 57, 100, 48, 97, 51, 99, 97, 55, 98, 102, 53, 56, 57, 100, 99, 55, 57, 57, 51, 49, 97, 53, 101, 51, 49, 48, 98, 99, 101, 100, 51, 100, 57, 55, 55, 57, 48, 54, 98, 99, 50, 56, 57, 54, 101, 97, 49, 99, 101, 99, 97, 53, 97, 56, 50, 52, 101, 57, 49, 97, 57, 53, 49, 34, 10, 125];
+#[test]
+fn submit_api_batch_registers_all_feeds() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KylinOracle::submit_api_batch(
+            RuntimeOrigin::signed(alice()),
+            vec![api_feed(b"btc_usd"), api_feed(b"eth_usd")],
+        ));
+
+        let alice_id = CreatorId::AccountId(alice());
+        assert!(KylinOracle::api_feeds(&alice_id, key(b"btc_usd")).is_some());
+        assert!(KylinOracle::api_feeds(&alice_id, key(b"eth_usd")).is_some());
+        let new_feeds = System::events()
+            .into_iter()
+            .filter(|r| matches!(r.event, RuntimeEvent::KylinOracle(crate::Event::NewApiFeed { .. })))
+            .count();
+        assert_eq!(new_feeds, 2);
+    });
+}
 
-    let mut pending_request = testing::PendingRequest {
-        method: "POST".into(),
-        uri: "https://api.kylin-node.co.uk/submit".into(),
-        body: sample_body,
-        response: Some(br#"{"USD": 155.23}"#.to_vec()),
-        sent: true,
-        ..Default::default()
-    };
+#[test]
+fn submit_api_batch_over_feed_cap_is_rejected_wholesale() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KylinOracle::submit_api_batch(
+            RuntimeOrigin::signed(alice()),
+            vec![api_feed(b"btc_usd"), api_feed(b"eth_usd")],
+        ));
+
+        assert_noop!(
+            KylinOracle::submit_api_batch(
+                RuntimeOrigin::signed(alice()),
+                vec![api_feed(b"dot_usd"), api_feed(b"ksm_usd")],
+            ),
+            Error::<Test>::TooManyFeeds
+        );
+        assert!(KylinOracle::api_feeds(CreatorId::AccountId(alice()), key(b"dot_usd")).is_none());
+    });
+}
 
-    pending_request.headers.push(("x-api-key".into(), "test_api_key".into()));
-    pending_request.headers.push(("content-type".into(), "application/json".into()));
-    state.expect_request(pending_request);
+#[test]
+fn submit_api_batch_rejects_duplicate_keys() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            KylinOracle::submit_api_batch(
+                RuntimeOrigin::signed(alice()),
+                vec![api_feed(b"btc_usd"), api_feed(b"btc_usd")],
+            ),
+            Error::<Test>::DuplicateFeedKey
+        );
+    });
 }
 
-fn mock_query_response(state: &mut testing::OffchainState) {
-    let pending_request = testing::PendingRequest {
-        // 99d0a3ca7bf589dc79931a5e310bced3d977906bc2896ea1ceca5a824e91a951
-        method: "GET".into(),
-        uri: "https://api.kylin-node.co.uk/query?hash=99d0a3ca7bf589dc79931a5e310bced3d977906bc2896ea1ceca5a824e91a951".into(),
-        response: Some(br#"{"USD": 155.23}"#.to_vec()),
-        sent: true,
-        ..Default::default()
-    };
-    state.expect_request(pending_request);
+fn sibling(para_id: u32) -> RuntimeOrigin {
+    cumulus_pallet_xcm::Origin::SiblingParachain(para_id.into()).into()
 }
 
 #[test]
-fn should_award_query_fees() {
-    let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
-    let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
-    let additional_amount = 10_000;
-    let query_reward_fee = 126743754;
-    let free_balance = <pallet_balances::Pallet<Test> as Currency<AccountId>>::free_balance;
+fn failed_query_answer_is_retried() {
+    new_test_ext().execute_with(|| {
+        Values::<Test>::insert(key(b"btc_usd"), TimestampedValue { value: 42, timestamp: 1_000 });
 
-    let (offchain, offchain_state) = testing::TestOffchainExt::new();
-    let (pool, _pool_state) = testing::TestTransactionPoolExt::new();
+        XcmSendFails::set(true);
+        assert_ok!(KylinOracle::xcm_query_data(sibling(2000), key(b"btc_usd")));
+        assert_eq!(KylinOracle::pending_responses().len(), 1);
 
-    let keystore = KeyStore::new();
-    let mut t = sp_io::TestExternalities::default();
-    t.register_extension(OffchainWorkerExt::new(offchain));
-    t.register_extension(TransactionPoolExt::new(pool));
-    t.register_extension(KeystoreExt(Arc::new(keystore)));
-    t.execute_with(|| {
-        let _ = <pallet_balances::Pallet<Test> as Currency<AccountId>>::deposit_creating(
-                        &alice,
-                        additional_amount
-        );
-
-        let _ =  <pallet_balances::Pallet<Test> as Currency<AccountId>>::deposit_creating(
-                        &bob,
-                        additional_amount
-                    );
-        
-        let initial_alice_balance = <pallet_balances::Pallet<Test> as Currency<AccountId>>::free_balance(&alice);
-        let initial_bob_balance = <pallet_balances::Pallet<Test> as Currency<AccountId>>::free_balance(&bob);
-
-        let sample_data = b"{sample_data}".to_vec();
-        let mut processed_requests: Vec<u64> = Vec::new();
-        let mut key = 10000000u64;
-        let mut alice_fee = 0;
-        let mut bob_fee = 0;
-        processed_requests.push(key);
-
-        // key = 10000000u64, data index = 10000000u64 weight = 65_955_000 + 100 * 3 + 1000 * 3
-        //     (65_955_000 as Weight)
-        //         .saturating_add(RocksDbWeight::get().reads(3 as Weight))
-        //         .saturating_add(RocksDbWeight::get().writes(3 as Weight))
-        let submit_price_feed_fee = 65_955_000 + 100 * 3 + 1000 * 3;
-        alice_fee += submit_price_feed_fee;
-        println!("alice: submit_price_feed fee {}", submit_price_feed_fee);
-        mock_submit_response(&mut offchain_state.write());       
-        KylinOracle::submit_price_feed(
-            Origin::signed(alice),
-            None,
-            str::from_utf8(b"btc_usd").unwrap().as_bytes().to_vec(),
-        )
-        .unwrap();
+        XcmSendFails::set(false);
+        KylinOracle::on_initialize(2);
+        assert!(KylinOracle::pending_responses().is_empty());
+    });
+}
 
-        println!("alice step0 balance: {}", free_balance(&alice));
-
-        // (65_955_000 as Weight)
-        // .saturating_add(T::DbWeight::get().reads(3 as Weight))
-        // .saturating_add(T::DbWeight::get().writes(3 as Weight))
-        let submit_data_signed_feed_fee = 65_955_000 + 100 * 3 + 1000 * 3;
-        alice_fee += submit_data_signed_feed_fee;
-        println!("alice: submit_price_feed fee {}", submit_data_signed_feed_fee);
-        KylinOracle::submit_data_signed(
-            Origin::signed(alice),
-            1,
-            key,
-            sample_data.clone()
-        )
-        .unwrap();
+#[test]
+fn query_for_missing_key_is_rejected_with_no_value_for_key() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            KylinOracle::xcm_query_data(sibling(2000), key(b"btc_usd")),
+            Error::<Test>::NoValueForKey
+        );
+        assert!(SentXcm::get().is_empty());
 
-        println!("alice step1 balance: {}", free_balance(&alice));
+        Values::<Test>::insert(key(b"btc_usd"), TimestampedValue { value: 42, timestamp: 1_000 });
+        assert_ok!(KylinOracle::xcm_query_data(sibling(2000), key(b"btc_usd")));
+        assert_eq!(SentXcm::get().len(), 1);
+        assert!(KylinOracle::pending_responses().is_empty());
+    });
+}
 
+#[test]
+fn failed_query_answer_is_abandoned_after_retries() {
+    new_test_ext().execute_with(|| {
+        Values::<Test>::insert(key(b"btc_usd"), TimestampedValue { value: 42, timestamp: 1_000 });
+
+        XcmSendFails::set(true);
+        assert_ok!(KylinOracle::xcm_query_data(sibling(2000), key(b"btc_usd")));
+        for n in 2..4 {
+            KylinOracle::on_initialize(n);
+            assert_eq!(KylinOracle::pending_responses().len(), 1);
+        }
+        KylinOracle::on_initialize(4);
 
-        KylinOracle::fetch_data_and_send_raw_unsigned(1).unwrap();
-        KylinOracle::clear_processed_requests_unsigned(
-            Origin::none(),
-            1,
-            processed_requests.clone(),
-        )
-        .unwrap();
+        assert!(KylinOracle::pending_responses().is_empty());
+        System::assert_last_event(RuntimeEvent::KylinOracle(crate::Event::QueryAnswerAbandoned {
+            para_id: 2000.into(),
+            key: b"btc_usd".to_vec(),
+        }));
+    });
+}
 
-        mock_post_response(&mut offchain_state.write());
-        KylinOracle::fetch_data_and_send_raw_unsigned(2).unwrap();
-    
-        // key = 10000001u64, data index = 10000001u64
-        key = 10000001u64;
-        mock_query_response(&mut offchain_state.write());
-
-        println!("alice step2 balance: {}", free_balance(&alice));
-
-        // (121_180_000 as Weight) // Standard Error: 0
-        // .saturating_add(RocksDbWeight::get().reads(4 as Weight))
-        // .saturating_add(RocksDbWeight::get().writes(2 as Weight))
-        // base_fee + len_fee + adjested_weight_fee
-        let query_data_fee = 121_180_000 + 100 * 4 + 1000 * 2;
-        bob_fee += query_data_fee;
-        println!("bob: query_data fee {}", query_data_fee);
-        KylinOracle::query_data(
-            Origin::signed(bob),
-            None,
-            str::from_utf8(b"price_feeding").unwrap().as_bytes().to_vec(),
-        )
-        .unwrap();
+fn last_value_combined() -> Option<(TimestampedValueT, Vec<CreatorId<AccountId>>)> {
+    System::events().into_iter().rev().find_map(|r| match r.event {
+        RuntimeEvent::KylinOracle(crate::Event::ValueCombined { value, contributors, .. }) =>
+            Some((value, contributors)),
+        _ => None,
+    })
+}
 
-        println!("alice step3 balance: {}", free_balance(&alice));
-
-        // (20_716_000 as Weight) // Standard Error: 0
-        // .saturating_add(RocksDbWeight::get().reads(2 as Weight))
-        // .saturating_add(RocksDbWeight::get().writes(1 as Weight))
-        let submit_data_signed_fee = 20_716_000 + 100 * 2 + 1000 * 1;
-        bob_fee += submit_data_signed_fee;
-        println!("bob: submit_data_signed fee {}", submit_data_signed_fee);
-        KylinOracle::submit_data_signed(
-            Origin::signed(bob),
-            2,
-            key,
-            sample_data.clone()
-        )
-        .unwrap();
-        KylinOracle::clear_api_queue_unsigned(
-            Origin::none(),
-            2,
-            processed_requests.clone(),
-        )
-        .unwrap();
-        KylinOracle::fetch_data_and_send_raw_unsigned(3).unwrap();
-        println!("alice step4 balance: {}", free_balance(&alice));
+#[test]
+fn value_combined_names_median_operator() {
+    new_test_ext().execute_with(|| {
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 300)]));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(charlie()), vec![(key(b"btc_usd"), 200)]));
+
+        assert_eq!(
+            last_value_combined(),
+            Some((
+                TimestampedValue { value: 200, timestamp: 10_000 },
+                vec![CreatorId::AccountId(charlie())]
+            ))
+        );
+    });
+}
 
+#[test]
+fn get_with_raws_returns_combined_and_sorted_raws() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(KylinOracle::get_with_raws(&key(b"btc_usd")), (None, vec![]));
+
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(charlie()), vec![(key(b"btc_usd"), 200)]));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 300)]));
+
+        let mut raws = vec![
+            (CreatorId::AccountId(alice()), TimestampedValue { value: 100, timestamp: 10_000 }),
+            (CreatorId::AccountId(bob()), TimestampedValue { value: 300, timestamp: 10_000 }),
+            (CreatorId::AccountId(charlie()), TimestampedValue { value: 200, timestamp: 10_000 }),
+        ];
+        raws.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            KylinOracle::get_with_raws(&key(b"btc_usd")),
+            (Some(TimestampedValue { value: 200, timestamp: 10_000 }), raws)
+        );
+    });
+}
 
-        let free_alice_balance = free_balance(&alice);
-        let free_bob_balance = free_balance(&bob);
+#[test]
+fn value_combined_names_every_operator_of_an_averaging_combine() {
+    new_test_ext().execute_with(|| {
+        MeanWins::set(true);
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 300)]));
+
+        let mut contributors = vec![CreatorId::AccountId(alice()), CreatorId::AccountId(bob())];
+        contributors.sort();
+        assert_eq!(
+            last_value_combined(),
+            Some((TimestampedValue { value: 200, timestamp: 10_000 }, contributors))
+        );
+    });
+}
 
-        println!("init alice balance {}, free alice balance {}", initial_alice_balance, free_alice_balance);
-        println!("init bob balance {}, free bob balance {}", initial_bob_balance, free_bob_balance);
-        println!("alice fee used {}, bob fee used {}", alice_fee, bob_fee);
+#[test]
+fn value_combined_names_latest_operator() {
+    new_test_ext().execute_with(|| {
+        LatestWins::set(true);
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        Timestamp::set_timestamp(20_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 200)]));
 
         assert_eq!(
-            free_alice_balance,
-            initial_alice_balance + query_reward_fee,
+            last_value_combined(),
+            Some((
+                TimestampedValue { value: 200, timestamp: 20_000 },
+                vec![CreatorId::AccountId(bob())]
+            ))
         );
     });
+}
 
-}
\ No newline at end of file
+#[test]
+fn offchain_sweep_resumes_from_cursor_when_out_of_budget() {
+    const PHRASE: &str =
+        "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
+    let (offchain, offchain_state) = testing::TestOffchainExt::new();
+    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+    let keystore = KeyStore::new();
+    SyncCryptoStore::sr25519_generate_new(
+        &keystore,
+        kylin_oracle::KEY_TYPE,
+        Some(&format!("{}/hunter1", PHRASE)),
+    )
+    .unwrap();
+
+    let mut t = new_test_ext();
+    t.register_extension(OffchainWorkerExt::new(offchain.clone()));
+    t.register_extension(OffchainDbExt::new(offchain));
+    t.register_extension(TransactionPoolExt::new(pool));
+    t.register_extension(KeystoreExt(Arc::new(keystore)));
+    t.execute_with(|| {
+        for name in [&b"btc_usd"[..], b"eth_usd", b"dot_usd"] {
+            assert_ok!(KylinOracle::submit_api(
+                RuntimeOrigin::signed(alice()),
+                key(name),
+                b"https://api.kylin-node.co.uk/prices".to_vec(),
+                b"/USD".to_vec(),
+                None,
+                None,
+                None,
+                None,
+            ));
+        }
+        let order: Vec<Vec<u8>> = ApiFeeds::<Test>::iter_keys()
+            .map(|(cid, k)| ApiFeeds::<Test>::hashed_key_for(&cid, &k))
+            .collect();
+        let cursor = || {
+            StorageValueRef::persistent(b"kylin_oracle::sweep_cursor").get::<Vec<u8>>().unwrap()
+        };
+
+        // A zero budget lets every run fetch a single feed before yielding.
+        for (n, expected_cursor) in [(1, Some(order[0].clone())), (2, Some(order[1].clone())), (3, None)] {
+            offchain_state.write().expect_request(testing::PendingRequest {
+                method: "GET".into(),
+                uri: "https://api.kylin-node.co.uk/prices".into(),
+                response: Some(br#"{"USD": 155.23}"#.to_vec()),
+                sent: true,
+                ..Default::default()
+            });
+            KylinOracle::fetch_api_and_feed_data(n).unwrap();
+            assert_eq!(cursor(), expected_cursor);
+
+            let tx = pool_state.write().transactions.pop().unwrap();
+            let tx = Extrinsic::decode(&mut &*tx).unwrap();
+            match tx.call {
+                RuntimeCall::KylinOracle(crate::Call::feed_data { values }) => assert_eq!(values.len(), 1),
+                _ => panic!("unexpected call"),
+            }
+        }
+    });
+}
+
+#[test]
+fn offchain_sweep_fetches_feeds_concurrently_up_to_the_cap() {
+    const PHRASE: &str =
+        "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
+    let (offchain, offchain_state) = testing::TestOffchainExt::new();
+    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+    let keystore = KeyStore::new();
+    SyncCryptoStore::sr25519_generate_new(
+        &keystore,
+        kylin_oracle::KEY_TYPE,
+        Some(&format!("{}/hunter1", PHRASE)),
+    )
+    .unwrap();
+
+    let mut t = new_test_ext();
+    t.register_extension(OffchainWorkerExt::new(offchain.clone()));
+    t.register_extension(OffchainDbExt::new(offchain));
+    t.register_extension(TransactionPoolExt::new(pool));
+    t.register_extension(KeystoreExt(Arc::new(keystore)));
+    t.execute_with(|| {
+        MaxConcurrentFetches::set(2);
+        for name in [&b"btc_usd"[..], b"eth_usd", b"dot_usd"] {
+            assert_ok!(KylinOracle::submit_api(
+                RuntimeOrigin::signed(alice()),
+                key(name),
+                b"https://api.kylin-node.co.uk/prices".to_vec(),
+                b"/USD".to_vec(),
+                None,
+                None,
+                None,
+                None,
+            ));
+        }
+        let order: Vec<_> = ApiFeeds::<Test>::iter_keys().collect();
+        let expect = |body: &[u8]| {
+            offchain_state.write().expect_request(testing::PendingRequest {
+                method: "GET".into(),
+                uri: "https://api.kylin-node.co.uk/prices".into(),
+                response: Some(body.to_vec()),
+                sent: true,
+                ..Default::default()
+            });
+        };
+        let submitted = || {
+            let mut calls: Vec<_> = pool_state
+                .write()
+                .transactions
+                .drain(..)
+                .map(|tx| Extrinsic::decode(&mut &*tx).unwrap().call)
+                .collect();
+            calls.sort_by_key(|call| matches!(call, RuntimeCall::KylinOracle(crate::Call::report_fetch_errors { .. })));
+            calls
+        };
+
+        // The zero budget still lets the first batch through, which holds two requests at
+        // once. A third request would be unexpected and fail the mocked HTTP host.
+        expect(br#"{"USD": 155.23}"#);
+        expect(b"not json");
+        KylinOracle::fetch_api_and_feed_data(1).unwrap();
+        assert_eq!(
+            submitted(),
+            vec![
+                RuntimeCall::KylinOracle(crate::Call::feed_data { values: vec![(order[0].1.clone(), 155_230_000)] }),
+                RuntimeCall::KylinOracle(crate::Call::report_fetch_errors {
+                    errors: vec![(order[1].0.clone(), order[1].1.clone(), FetchErrorKind::InvalidJson)],
+                }),
+            ]
+        );
+
+        // The next run picks up the remaining feed.
+        expect(br#"{"USD": 155.23}"#);
+        KylinOracle::fetch_api_and_feed_data(2).unwrap();
+        assert_eq!(
+            submitted(),
+            vec![RuntimeCall::KylinOracle(crate::Call::feed_data { values: vec![(order[2].1.clone(), 155_230_000)] })]
+        );
+    });
+}
+
+#[test]
+fn disabled_fetching_skips_offchain_sweep() {
+    const PHRASE: &str =
+        "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
+    let (offchain, offchain_state) = testing::TestOffchainExt::new();
+    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+    let keystore = KeyStore::new();
+    SyncCryptoStore::sr25519_generate_new(
+        &keystore,
+        kylin_oracle::KEY_TYPE,
+        Some(&format!("{}/hunter1", PHRASE)),
+    )
+    .unwrap();
+
+    let mut t = new_test_ext();
+    t.register_extension(OffchainWorkerExt::new(offchain.clone()));
+    t.register_extension(OffchainDbExt::new(offchain));
+    t.register_extension(TransactionPoolExt::new(pool));
+    t.register_extension(KeystoreExt(Arc::new(keystore)));
+    t.execute_with(|| {
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"btc_usd"),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
+        let expect_fetch = || {
+            offchain_state.write().expect_request(testing::PendingRequest {
+                method: "GET".into(),
+                uri: "https://api.kylin-node.co.uk/prices".into(),
+                response: Some(br#"{"USD": 155.23}"#.to_vec()),
+                sent: true,
+                ..Default::default()
+            })
+        };
+        let submitted = || pool_state.read().transactions.len();
+
+        expect_fetch();
+        KylinOracle::offchain_worker(2);
+        assert_eq!(submitted(), 1);
+
+        assert_noop!(
+            KylinOracle::set_fetching_enabled(RuntimeOrigin::signed(alice()), false),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(KylinOracle::set_fetching_enabled(RuntimeOrigin::root(), false));
+        assert!(!KylinOracle::fetching_enabled());
+        System::assert_last_event(RuntimeEvent::KylinOracle(crate::Event::FetchingEnabledSet { enabled: false }));
+
+        // No request is expected, so a fetch would fail the test.
+        KylinOracle::offchain_worker(3);
+        assert_eq!(submitted(), 1);
+
+        assert_ok!(KylinOracle::set_fetching_enabled(RuntimeOrigin::root(), true));
+        expect_fetch();
+        KylinOracle::offchain_worker(4);
+        assert_eq!(submitted(), 2);
+    });
+}
+
+fn submitted_feed_transactions(sign_with_all_accounts: bool) -> usize {
+    const PHRASE: &str =
+        "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
+    let (offchain, offchain_state) = testing::TestOffchainExt::new();
+    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+    let keystore = KeyStore::new();
+    for seed in ["hunter1", "hunter2"] {
+        SyncCryptoStore::sr25519_generate_new(
+            &keystore,
+            kylin_oracle::KEY_TYPE,
+            Some(&format!("{}/{}", PHRASE, seed)),
+        )
+        .unwrap();
+    }
+
+    let mut t = new_test_ext();
+    t.register_extension(OffchainWorkerExt::new(offchain.clone()));
+    t.register_extension(OffchainDbExt::new(offchain));
+    t.register_extension(TransactionPoolExt::new(pool));
+    t.register_extension(KeystoreExt(Arc::new(keystore)));
+    t.execute_with(|| {
+        SignWithAllAccounts::set(sign_with_all_accounts);
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"btc_usd"),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
+        offchain_state.write().expect_request(testing::PendingRequest {
+            method: "GET".into(),
+            uri: "https://api.kylin-node.co.uk/prices".into(),
+            response: Some(br#"{"USD": 155.23}"#.to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+        KylinOracle::fetch_api_and_feed_data(1).unwrap();
+    });
+    let submitted = pool_state.read().transactions.len();
+    submitted
+}
+
+#[test]
+fn single_account_mode_submits_one_transaction() {
+    assert_eq!(submitted_feed_transactions(false), 1);
+}
+
+#[test]
+fn all_accounts_mode_submits_one_transaction_per_key() {
+    assert_eq!(submitted_feed_transactions(true), 2);
+}
+
+parameter_types! {
+    pub const AgreementTolerance: i64 = 5;
+    pub AgreementThreshold: Perbill = Perbill::from_rational(2u32, 3u32);
+}
+
+type Supermajority = SupermajorityCombineData<Test, AgreementTolerance, AgreementThreshold, ExpiresIn>;
+
+fn reports(values: &[i64]) -> Vec<TimestampedValueT> {
+    values.iter().map(|&value| TimestampedValue { value, timestamp: 1_000 }).collect()
+}
+
+#[test]
+fn supermajority_combine_publishes_cluster_median() {
+    new_test_ext().execute_with(|| {
+        // Two of the three operators agree within the tolerance band.
+        let combined = Supermajority::combine_data(&key(b"btc_usd"), reports(&[104, 500, 100]), None);
+        assert_eq!(combined, Some(TimestampedValue { value: 104, timestamp: 1_000 }));
+    });
+}
+
+#[test]
+fn supermajority_combine_suppresses_split_reports() {
+    new_test_ext().execute_with(|| {
+        let prev = Some(TimestampedValue { value: 100, timestamp: 500 });
+        assert_eq!(Supermajority::combine_data(&key(b"btc_usd"), reports(&[100, 500]), prev), None);
+    });
+}
+
+parameter_types! {
+    pub TrimFraction: Perbill = Perbill::from_percent(20);
+    pub const TrimmedMinimumCount: u32 = 3;
+}
+
+type TrimmedMean = TrimmedMeanCombineData<Test, TrimFraction, TrimmedMinimumCount, ExpiresIn>;
+
+#[test]
+fn trimmed_mean_combine_ignores_outlier() {
+    new_test_ext().execute_with(|| {
+        // The plain mean would be 2081.
+        let combined = TrimmedMean::combine_data(&key(b"btc_usd"), reports(&[103, 10_000, 101, 100, 102]), None);
+        assert_eq!(combined, Some(TimestampedValue { value: 102, timestamp: 1_000 }));
+    });
+}
+
+#[test]
+fn trimmed_mean_combine_requires_minimum_count() {
+    new_test_ext().execute_with(|| {
+        let prev = Some(TimestampedValue { value: 100, timestamp: 500 });
+        assert_eq!(TrimmedMean::combine_data(&key(b"btc_usd"), reports(&[100, 101]), prev), None);
+    });
+}
+
+parameter_types! {
+    pub CappedBand: Perbill = Perbill::from_percent(5);
+    pub const CappedMinBand: u64 = 30;
+}
+
+type CappedMean = CappedMeanCombineData<Test, CappedBand, CappedMinBand, ExpiresIn>;
+
+#[test]
+fn capped_mean_combine_clamps_extreme_report() {
+    new_test_ext().execute_with(|| {
+        let prev = Some(TimestampedValue { value: 1_000, timestamp: 500 });
+        // 10_000 counts as 1_050, the plain mean would be 4_000.
+        let combined = CappedMean::combine_data(&key(b"btc_usd"), reports(&[1_000, 10_000, 1_010]), prev);
+        assert_eq!(combined, Some(TimestampedValue { value: 1_020, timestamp: 1_000 }));
+    });
+}
+
+#[test]
+fn capped_mean_combine_passes_reports_within_band() {
+    new_test_ext().execute_with(|| {
+        let prev = Some(TimestampedValue { value: 1_000, timestamp: 500 });
+        let combined = CappedMean::combine_data(&key(b"btc_usd"), reports(&[990, 1_040, 1_030]), prev);
+        assert_eq!(combined, Some(TimestampedValue { value: 1_020, timestamp: 1_000 }));
+        // Without a previous value, nothing is clamped.
+        let combined = CappedMean::combine_data(&key(b"btc_usd"), reports(&[1_000, 10_000, 1_010]), None);
+        assert_eq!(combined, Some(TimestampedValue { value: 4_003, timestamp: 1_000 }));
+    });
+}
+
+#[test]
+fn capped_mean_combine_moves_off_zero_by_min_band() {
+    new_test_ext().execute_with(|| {
+        let prev = Some(TimestampedValue { value: 0, timestamp: 500 });
+        // 5% of 0 would pin the value, the minimum band of 30 still lets it move.
+        let combined = CappedMean::combine_data(&key(b"btc_usd"), reports(&[100, 100, 100]), prev);
+        assert_eq!(combined, Some(TimestampedValue { value: 30, timestamp: 1_000 }));
+        let prev = combined;
+        let combined = CappedMean::combine_data(&key(b"btc_usd"), reports(&[100, 100, 100]), prev);
+        assert_eq!(combined, Some(TimestampedValue { value: 60, timestamp: 1_000 }));
+    });
+}
+
+parameter_types! {
+    pub static DecayHalfLife: u128 = 1_000;
+}
+
+type Decay = DecayCombineData<Test, DecayHalfLife, ExpiresIn>;
+
+fn aged_reports(reports: &[(i64, u128)]) -> Vec<TimestampedValueT> {
+    reports.iter().map(|&(value, timestamp)| TimestampedValue { value, timestamp }).collect()
+}
+
+#[test]
+fn decay_combine_with_short_half_life_follows_latest() {
+    new_test_ext().execute_with(|| {
+        Timestamp::set_timestamp(100_000);
+        DecayHalfLife::set(1);
+        let values = aged_reports(&[(100, 90_000), (200, 99_000), (300, 100_000)]);
+        assert_eq!(
+            Decay::combine_data(&key(b"btc_usd"), values, None),
+            Some(TimestampedValue { value: 300, timestamp: 100_000 })
+        );
+    });
+}
+
+#[test]
+fn decay_combine_with_long_half_life_approaches_mean() {
+    new_test_ext().execute_with(|| {
+        Timestamp::set_timestamp(100_000);
+        DecayHalfLife::set(u64::MAX as u128);
+        let values = aged_reports(&[(100, 90_000), (200, 99_000), (300, 100_000)]);
+        assert_eq!(
+            Decay::combine_data(&key(b"btc_usd"), values, None),
+            Some(TimestampedValue { value: 200, timestamp: 100_000 })
+        );
+    });
+}
+
+#[test]
+fn decay_combine_halves_weight_per_half_life() {
+    new_test_ext().execute_with(|| {
+        Timestamp::set_timestamp(100_000);
+        DecayHalfLife::set(1_000);
+        // Weights 1 and 1/2 give (300 * 1 + 0 * 1/2) / 1.5 = 200.
+        let values = aged_reports(&[(0, 99_000), (300, 100_000)]);
+        assert_eq!(
+            Decay::combine_data(&key(b"btc_usd"), values, None),
+            Some(TimestampedValue { value: 200, timestamp: 100_000 })
+        );
+
+        let prev = Some(TimestampedValue { value: 42, timestamp: 1_000 });
+        assert_eq!(Decay::combine_data(&key(b"btc_usd"), vec![], prev), prev);
+    });
+}
+
+#[test]
+fn feeds_of_lists_only_the_creators_feeds() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KylinOracle::submit_api_batch(
+            RuntimeOrigin::signed(alice()),
+            vec![api_feed(b"btc_usd"), api_feed(b"eth_usd")],
+        ));
+        assert_ok!(KylinOracle::submit_api_batch(RuntimeOrigin::signed(bob()), vec![api_feed(b"dot_usd")]));
+
+        let alice = CreatorId::AccountId(alice());
+        let mut feeds = KylinOracle::feeds_of(alice.clone());
+        feeds.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            feeds,
+            vec![
+                (key(b"btc_usd"), ApiFeeds::<Test>::get(&alice, key(b"btc_usd")).unwrap()),
+                (key(b"eth_usd"), ApiFeeds::<Test>::get(&alice, key(b"eth_usd")).unwrap()),
+            ]
+        );
+    });
+}
+
+/// Layout of `KylinFeed::xcm_feed_back` in the Kylin runtime
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+#[allow(non_camel_case_types)]
+enum KylinMockFunc {
+    #[codec(index = 7u8)]
+    xcm_feed_back {
+        key: Vec<u8>,
+        value: i64,
+        decimals: u8,
+        pair: Option<(Vec<u8>, Vec<u8>)>,
+        confidence: Option<Permill>,
+    },
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode)]
+#[allow(non_camel_case_types)]
+enum KylinMockCall {
+    #[codec(index = 168u8)]
+    KylinFeed(KylinMockFunc),
+}
+
+fn sent_transact_call() -> Vec<u8> {
+    match SentXcm::get().as_slice() {
+        [Xcm(instructions)] => match instructions.as_slice() {
+            [Transact { call, .. }] => call.clone().into_encoded(),
+            _ => panic!("unexpected instructions"),
+        },
+        _ => panic!("expected a single message"),
+    }
+}
+
+#[test]
+fn published_value_carries_decimals_over_xcm() {
+    new_test_ext().execute_with(|| {
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 155_230_000)]));
+        assert_eq!(
+            KylinOracle::get_with_decimals(&key(b"btc_usd")),
+            Some((TimestampedValue { value: 155_230_000, timestamp: 10_000 }, VALUE_DECIMALS))
+        );
+
+        SentXcm::set(vec![]);
+        assert_ok!(KylinOracle::xcm_query_data(sibling(2000), key(b"btc_usd")));
+        let call = sent_transact_call();
+        assert_eq!(
+            KylinMockCall::decode(&mut &call[..]).unwrap(),
+            KylinMockCall::KylinFeed(KylinMockFunc::xcm_feed_back {
+                key: b"btc_usd".to_vec(),
+                value: 155_230_000,
+                decimals: VALUE_DECIMALS,
+                pair: None,
+                confidence: None,
+            })
+        );
+    });
+}
+
+#[test]
+fn query_answer_targets_configured_feedback_indices() {
+    new_test_ext().execute_with(|| {
+        Values::<Test>::insert(key(b"btc_usd"), TimestampedValue { value: 42, timestamp: 1_000 });
+        FeedbackPalletIndex::set(51);
+        FeedbackCallIndex::set(3);
+
+        assert_ok!(KylinOracle::xcm_query_data(sibling(2000), key(b"btc_usd")));
+        let call = sent_transact_call();
+        assert_eq!(&call[..2], &[51, 3]);
+        assert_eq!(
+            FeedbackCall::decode(&mut &call[..]).unwrap(),
+            FeedbackCall {
+                pallet_index: 51,
+                call_index: 3,
+                key: b"btc_usd".to_vec(),
+                value: 42,
+                decimals: VALUE_DECIMALS,
+                pair: None,
+                confidence: None,
+            }
+        );
+        assert_eq!(
+            call[2..],
+            (b"btc_usd".to_vec(), 42i64, VALUE_DECIMALS, None::<(Vec<u8>, Vec<u8>)>, None::<Permill>).encode()[..]
+        );
+    });
+}
+
+#[test]
+fn boolean_feed_maps_true_and_false() {
+    let json: JValue = serde_json::from_slice(br#"{"isHalted": true, "isLive": false}"#).unwrap();
+    assert_eq!(KylinOracle::extract_value(&json, "/isHalted", true), Ok(1));
+    assert_eq!(KylinOracle::extract_value(&json, "/isLive", true), Ok(0));
+}
+
+#[test]
+fn numeric_feed_rejects_boolean_value() {
+    let json: JValue = serde_json::from_slice(br#"{"isHalted": true, "USD": 1.5}"#).unwrap();
+    assert_eq!(KylinOracle::extract_value(&json, "/isHalted", false), Err(FetchErrorKind::InvalidValue));
+    assert_eq!(KylinOracle::extract_value(&json, "/USD", false), Ok(1_500_000));
+}
+
+#[test]
+fn set_feed_boolean_flags_own_feed() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            KylinOracle::set_feed_boolean(RuntimeOrigin::signed(alice()), key(b"is_halted"), true),
+            DispatchError::CannotLookup
+        );
+
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"is_halted"),
+            b"https://api.kylin-node.co.uk/status".to_vec(),
+            b"/isHalted".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_ok!(KylinOracle::set_feed_boolean(RuntimeOrigin::signed(alice()), key(b"is_halted"), true));
+        assert!(KylinOracle::boolean_feeds(CreatorId::AccountId(alice()), key(b"is_halted")));
+
+        assert_ok!(KylinOracle::remove_api(RuntimeOrigin::signed(alice()), key(b"is_halted")));
+        assert!(!KylinOracle::boolean_feeds(CreatorId::AccountId(alice()), key(b"is_halted")));
+    });
+}
+
+#[test]
+fn boolean_feed_is_published_without_decimals() {
+    new_test_ext().execute_with(|| {
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"is_halted"),
+            b"https://api.kylin-node.co.uk/status".to_vec(),
+            b"/isHalted".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_ok!(KylinOracle::set_feed_boolean(RuntimeOrigin::signed(alice()), key(b"is_halted"), true));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"is_halted"), 1)]));
+        assert_eq!(
+            KylinOracle::get_with_decimals(&key(b"is_halted")),
+            Some((TimestampedValue { value: 1, timestamp: 10_000 }, 0))
+        );
+
+        assert_ok!(KylinOracle::xcm_query_data(sibling(2000), key(b"is_halted")));
+        assert_eq!(
+            KylinMockCall::decode(&mut &sent_transact_call()[..]).unwrap(),
+            KylinMockCall::KylinFeed(KylinMockFunc::xcm_feed_back {
+                key: b"is_halted".to_vec(),
+                value: 1,
+                decimals: 0,
+                pair: None,
+                confidence: None,
+            })
+        );
+    });
+}
+
+#[test]
+fn replayed_xcm_feed_is_rejected() {
+    new_test_ext().execute_with(|| {
+        let values = vec![(key(b"btc_usd"), 100)];
+        assert_ok!(KylinOracle::xcm_feed_data(sibling(2000), values.clone(), Some(7)));
+        KylinOracle::on_finalize(1);
+
+        System::set_block_number(2);
+        assert_noop!(
+            KylinOracle::xcm_feed_data(sibling(2000), values.clone(), Some(7)),
+            Error::<Test>::DuplicateSubmission
+        );
+        assert_ok!(KylinOracle::xcm_feed_data(sibling(2000), values, Some(8)));
+        assert_eq!(KylinOracle::feed_nonces(ParaId::from(2000)).into_inner(), vec![7, 8]);
+    });
+}
+
+fn set_primary_and_fallback(name: &[u8]) {
+    assert_ok!(KylinOracle::set_source_groups(
+        RuntimeOrigin::root(),
+        key(name),
+        vec![
+            vec![CreatorId::AccountId(alice()), CreatorId::AccountId(bob())],
+            vec![CreatorId::AccountId(charlie())],
+        ],
+    ));
+}
+
+#[test]
+fn set_source_groups_requires_admin() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            KylinOracle::set_source_groups(RuntimeOrigin::signed(alice()), key(b"btc_usd"), vec![]),
+            DispatchError::BadOrigin
+        );
+        assert_noop!(
+            KylinOracle::set_source_groups(
+                RuntimeOrigin::root(),
+                key(b"btc_usd"),
+                vec![vec![CreatorId::AccountId(alice()), CreatorId::AccountId(bob()), CreatorId::AccountId(charlie())]],
+            ),
+            Error::<Test>::TooManySourceGroups
+        );
+    });
+}
+
+#[test]
+fn primary_group_with_quorum_is_used() {
+    new_test_ext().execute_with(|| {
+        MinimumCount::set(2);
+        set_primary_and_fallback(b"btc_usd");
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 200)]));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(charlie()), vec![(key(b"btc_usd"), 500)]));
+
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")).map(|x| x.value), Some(200));
+    });
+}
+
+#[test]
+fn stale_primary_group_falls_back() {
+    new_test_ext().execute_with(|| {
+        set_primary_and_fallback(b"btc_usd");
+        Timestamp::set_timestamp(1_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")).map(|x| x.value), Some(100));
+
+        // alice's report is older than `MaxRawValueAge` by now.
+        Timestamp::set_timestamp(70_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(charlie()), vec![(key(b"btc_usd"), 500)]));
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")).map(|x| x.value), Some(500));
+    });
+}
+
+#[test]
+fn no_group_with_quorum_publishes_nothing() {
+    new_test_ext().execute_with(|| {
+        MinimumCount::set(2);
+        set_primary_and_fallback(b"btc_usd");
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(charlie()), vec![(key(b"btc_usd"), 500)]));
+
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")), None);
+    });
+}
+
+#[test]
+fn consecutive_fetch_failures_reset_on_success() {
+    new_test_ext().execute_with(|| {
+        let btc = key(b"btc_usd");
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            btc.clone(),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
+        Timestamp::set_timestamp(1_000);
+        assert_ok!(KylinOracle::report_fetch_errors(
+            RuntimeOrigin::signed(alice()),
+            vec![(CreatorId::AccountId(alice()), btc.clone(), FetchErrorKind::Http)],
+        ));
+        Timestamp::set_timestamp(2_000);
+        assert_ok!(KylinOracle::report_fetch_errors(
+            RuntimeOrigin::signed(alice()),
+            vec![(CreatorId::AccountId(alice()), btc.clone(), FetchErrorKind::InvalidJson)],
+        ));
+
+        let health = KylinOracle::feed_health(CreatorId::AccountId(alice()), &btc);
+        assert_eq!(health.consecutive_failures, 2);
+        assert_eq!(health.last_error, Some((FetchErrorKind::InvalidJson, 2_000)));
+        assert_eq!(health.last_success, None);
+        assert_eq!(health.reporters, vec![alice()]);
+
+        // Any operator feeding the key counts as a success of the feed owning it.
+        System::set_block_number(5);
+        Timestamp::set_timestamp(3_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(btc.clone(), 100)]));
+
+        assert_eq!(
+            KylinOracle::oracle_diagnostics(),
+            vec![(
+                CreatorId::AccountId(alice()),
+                btc,
+                FeedHealth {
+                    last_error: Some((FetchErrorKind::InvalidJson, 2_000)),
+                    last_success: Some(5),
+                    consecutive_failures: 0,
+                    reporters: vec![],
+                },
+            )]
+        );
+    });
+}
+
+#[test]
+fn fetch_jitter_spreads_nodes_over_the_interval() {
+    let due_blocks = |seed: [u8; 32]| -> Vec<u32> {
+        (20..30).filter(|&n| KylinOracle::is_fetch_due(&seed, b"btc_usd", n, 10)).collect()
+    };
+
+    // Every node fetches the feed once per interval, each on its own block.
+    assert_eq!(due_blocks([1; 32]), vec![23]);
+    assert_eq!(due_blocks([2; 32]), vec![29]);
+    assert!((0..10).all(|n| KylinOracle::is_fetch_due(&[1; 32], b"btc_usd", n, 1)));
+}
+
+#[test]
+fn feed_data_skips_changes_within_deadband() {
+    new_test_ext().execute_with(|| {
+        UpdateDeadband::set(10);
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"btc_usd"),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+
+        KylinOracle::on_finalize(1);
+        System::set_block_number(2);
+        System::reset_events();
+        Timestamp::set_timestamp(20_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 109)]));
+        assert_eq!(
+            KylinOracle::raw_values(CreatorId::AccountId(alice()), key(b"btc_usd")),
+            Some(TimestampedValue { value: 100, timestamp: 10_000 })
+        );
+        assert!(System::events().is_empty());
+        assert_eq!(KylinOracle::feed_health(CreatorId::AccountId(alice()), key(b"btc_usd")).last_success, Some(1));
+
+        KylinOracle::on_finalize(2);
+        System::set_block_number(3);
+        Timestamp::set_timestamp(30_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 110)]));
+        assert_eq!(
+            KylinOracle::raw_values(CreatorId::AccountId(alice()), key(b"btc_usd")),
+            Some(TimestampedValue { value: 110, timestamp: 30_000 })
+        );
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")).map(|v| v.value), Some(110));
+    });
+}
+
+#[test]
+fn feed_data_refreshes_aging_value_within_deadband() {
+    new_test_ext().execute_with(|| {
+        UpdateDeadband::set(10);
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+
+        // Half of `MaxRawValueAge` has passed, so the unchanged value is stored again.
+        KylinOracle::on_finalize(1);
+        System::set_block_number(2);
+        Timestamp::set_timestamp(40_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert_eq!(
+            KylinOracle::raw_values(CreatorId::AccountId(alice()), key(b"btc_usd")),
+            Some(TimestampedValue { value: 100, timestamp: 40_000 })
+        );
+    });
+}
+
+fn submit_bounded_feed(min: Option<i64>, max: Option<i64>) {
+    assert_ok!(KylinOracle::submit_api(
+        RuntimeOrigin::signed(alice()),
+        key(b"btc_usd"),
+        b"https://api.kylin-node.co.uk/prices".to_vec(),
+        b"/USD".to_vec(),
+        min,
+        max,
+        None,
+        None,
+    ));
+}
+
+#[test]
+fn feed_data_drops_values_out_of_bounds() {
+    new_test_ext().execute_with(|| {
+        submit_bounded_feed(Some(100), Some(200));
+
+        assert_ok!(KylinOracle::feed_data(
+            RuntimeOrigin::signed(alice()),
+            vec![(key(b"btc_usd"), 99), (key(b"eth_usd"), 7)]
+        ));
+        assert_eq!(
+            last_feed_statuses(),
+            vec![
+                (key(b"btc_usd"), 99, FeedStatus::OutOfBounds),
+                (key(b"eth_usd"), 7, FeedStatus::Published),
+            ]
+        );
+        assert_eq!(KylinOracle::raw_values(CreatorId::AccountId(alice()), key(b"btc_usd")), None);
+        assert_eq!(KylinOracle::get(&key(b"eth_usd")).map(|v| v.value), Some(7));
+
+        KylinOracle::on_finalize(1);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 201)]));
+        assert_eq!(last_feed_statuses(), vec![(key(b"btc_usd"), 201, FeedStatus::OutOfBounds)]);
+
+        KylinOracle::on_finalize(1);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 200)]));
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")).map(|v| v.value), Some(200));
+    });
+}
+
+#[test]
+fn bounds_of_the_owning_feed_apply_to_every_operator() {
+    new_test_ext().execute_with(|| {
+        submit_bounded_feed(Some(100), Some(200));
+        assert_eq!(KylinOracle::feed_owners(key(b"btc_usd")), Some(CreatorId::AccountId(alice())));
+
+        // Bob has no feed of his own for the key, alice's bounds still hold him.
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 500)]));
+        assert_eq!(last_feed_statuses(), vec![(key(b"btc_usd"), 500, FeedStatus::OutOfBounds)]);
+        assert_eq!(KylinOracle::raw_values(CreatorId::AccountId(bob()), key(b"btc_usd")), None);
+
+        // Registering the key again doesn't take it over from alice.
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(bob()),
+            key(b"btc_usd"),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_eq!(KylinOracle::feed_owners(key(b"btc_usd")), Some(CreatorId::AccountId(alice())));
+
+        // Once alice's feed is gone, the key is unowned until submitted again.
+        assert_ok!(KylinOracle::remove_api(RuntimeOrigin::signed(alice()), key(b"btc_usd")));
+        assert_eq!(KylinOracle::feed_owners(key(b"btc_usd")), None);
+        KylinOracle::on_finalize(1);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 500)]));
+        assert_eq!(KylinOracle::raw_values(CreatorId::AccountId(bob()), key(b"btc_usd")).map(|v| v.value), Some(500));
+    });
+}
+
+#[test]
+fn feed_data_clamps_values_out_of_bounds_when_configured() {
+    new_test_ext().execute_with(|| {
+        ClampOutOfBounds::set(true);
+        submit_bounded_feed(Some(100), None);
+
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), -5)]));
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")).map(|v| v.value), Some(100));
+    });
+}
+
+#[test]
+fn submit_api_rejects_inverted_bounds() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            KylinOracle::submit_api(
+                RuntimeOrigin::signed(alice()),
+                key(b"btc_usd"),
+                b"https://api.kylin-node.co.uk/prices".to_vec(),
+                b"/USD".to_vec(),
+                Some(200),
+                Some(100),
+                None,
+                None,
+            ),
+            Error::<Test>::InvalidValueBounds
+        );
+    });
+}
+
+fn last_feed_statuses() -> Vec<(OracleKeyOf<Test>, i64, FeedStatus)> {
+    System::events().into_iter().rev().find_map(|r| match r.event {
+        RuntimeEvent::KylinOracle(crate::Event::NewFeedData { values, .. }) => Some(values),
+        _ => None,
+    }).unwrap()
+}
+
+#[test]
+fn new_feed_data_reports_quorum_progress() {
+    new_test_ext().execute_with(|| {
+        MinimumCount::set(2);
+        Timestamp::set_timestamp(10_000);
+
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert_eq!(last_feed_statuses(), vec![(key(b"btc_usd"), 100, FeedStatus::PendingQuorum)]);
+
+        assert_ok!(KylinOracle::feed_data(
+            RuntimeOrigin::signed(bob()),
+            vec![(key(b"btc_usd"), 102), (key(b"eth_usd"), 7)]
+        ));
+        assert_eq!(
+            last_feed_statuses(),
+            vec![
+                (key(b"btc_usd"), 102, FeedStatus::Published),
+                (key(b"eth_usd"), 7, FeedStatus::PendingQuorum),
+            ]
+        );
+    });
+}
+
+#[test]
+fn on_new_value_fires_once_per_fed_key() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KylinOracle::feed_data(
+            RuntimeOrigin::signed(alice()),
+            vec![(key(b"btc_usd"), 100), (key(b"eth_usd"), 7)]
+        ));
+        assert_ok!(KylinOracle::xcm_feed_data(sibling(2000), vec![(key(b"btc_usd"), 101)], None));
+
+        assert_eq!(
+            NewValues::get(),
+            vec![
+                (CreatorId::AccountId(alice()), b"btc_usd".to_vec(), 100),
+                (CreatorId::AccountId(alice()), b"eth_usd".to_vec(), 7),
+                (CreatorId::ParaId(2000.into()), b"btc_usd".to_vec(), 101),
+            ]
+        );
+    });
+}
+
+#[test]
+fn submit_api_rejects_over_length_url_and_vpath() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(
+            KylinOracle::submit_api(
+                RuntimeOrigin::signed(alice()),
+                key(b"btc_usd"),
+                vec![b'a'; 65],
+                b"/USD".to_vec(),
+                None,
+                None,
+                None,
+                None,
+            ),
+            Error::<Test>::TooLarge
+        );
+        assert_noop!(
+            KylinOracle::xcm_submit_api(
+                sibling(2000),
+                key(b"btc_usd"),
+                b"https://api.kylin-node.co.uk/prices".to_vec(),
+                vec![b'/'; 17],
+            ),
+            Error::<Test>::TooLarge
+        );
+
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"btc_usd"),
+            vec![b'a'; 64],
+            vec![b'/'; 16],
+            None,
+            None,
+            None,
+            None,
+        ));
+    });
+}
+
+#[test]
+fn strict_mode_waits_for_every_operator() {
+    new_test_ext().execute_with(|| {
+        StrictAllOperators::set(true);
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 101)]));
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")), None);
+
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(charlie()), vec![(key(b"btc_usd"), 102)]));
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")).map(|v| v.value), Some(101));
+
+        // With charlie out of the set, alice and bob are enough once the old reports expired.
+        RemovedMember::set(Some(charlie()));
+        KylinOracle::on_finalize(1);
+        Timestamp::set_timestamp(100_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 200)]));
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")).map(|v| v.value), Some(101));
+
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 200)]));
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")).map(|v| v.value), Some(200));
+    });
+}
+
+#[test]
+fn migration_to_v1_bounds_api_feeds() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    use migrations::v1::OldApiFeed;
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(0).put::<KylinOracle>();
+        let old_feed = |url: Vec<u8>| OldApiFeed {
+            requested_block_number: 7u64,
+            url: Some(url),
+            vpath: Some(b"/USD".to_vec()),
+        };
+        frame_support::storage::unhashed::put(
+            &ApiFeeds::<Test>::hashed_key_for(CreatorId::AccountId(alice()), key(b"btc_usd")),
+            &old_feed(b"https://api.kylin-node.co.uk/prices".to_vec()),
+        );
+        frame_support::storage::unhashed::put(
+            &ApiFeeds::<Test>::hashed_key_for(CreatorId::AccountId(alice()), key(b"eth_usd")),
+            &old_feed(vec![b'a'; 65]),
+        );
+
+        migrations::v1::migrate::<Test>();
+
+        let feed = KylinOracle::api_feeds(CreatorId::AccountId(alice()), key(b"btc_usd")).unwrap();
+        assert_eq!(feed.requested_block_number, 7);
+        assert_eq!(feed.url.map(|url| url.into_inner()), Some(b"https://api.kylin-node.co.uk/prices".to_vec()));
+        assert_eq!(feed.vpath.map(|vpath| vpath.into_inner()), Some(b"/USD".to_vec()));
+        assert_eq!((feed.min, feed.max), (None, None));
+        assert!(KylinOracle::api_feeds(CreatorId::AccountId(alice()), key(b"eth_usd")).is_none());
+        assert_eq!(KylinOracle::on_chain_storage_version(), 1);
+    });
+}
+
+parameter_types! {
+    pub static Stakes: Vec<(AccountId, u128)> = vec![];
+}
+
+pub struct MockStakeOf;
+impl sp_runtime::traits::Convert<CreatorId<AccountId>, u128> for MockStakeOf {
+    fn convert(cid: CreatorId<AccountId>) -> u128 {
+        Stakes::get()
+            .into_iter()
+            .find(|(account, _)| cid == CreatorId::AccountId(account.clone()))
+            .map_or(0, |(_, stake)| stake)
+    }
+}
+
+type StakeWeighted = StakeWeightedCombineData<Test, MockStakeOf, ExpiresIn>;
+
+#[test]
+fn stake_weighted_combine_follows_stake() {
+    new_test_ext().execute_with(|| {
+        Timestamp::set_timestamp(10_000);
+        let mut values = vec![];
+        for (operator, value) in [(alice(), 100), (bob(), 200), (charlie(), 300)] {
+            let value = TimestampedValue { value, timestamp: 10_000 };
+            RawValues::<Test>::insert(CreatorId::AccountId(operator), key(b"btc_usd"), value);
+            values.push(value);
+        }
+
+        // The plain median would be 200.
+        Stakes::set(vec![(alice(), 1), (bob(), 1), (charlie(), 5)]);
+        assert_eq!(
+            StakeWeighted::combine_data(&key(b"btc_usd"), values.clone(), None).map(|v| v.value),
+            Some(300)
+        );
+
+        // Without stake charlie is left out, equal stakes resolve to the lower value.
+        Stakes::set(vec![(alice(), 1), (bob(), 1), (charlie(), 0)]);
+        assert_eq!(
+            StakeWeighted::combine_data(&key(b"btc_usd"), values.clone(), None).map(|v| v.value),
+            Some(100)
+        );
+
+        let prev = Some(TimestampedValue { value: 42, timestamp: 1_000 });
+        Stakes::set(vec![]);
+        assert_eq!(StakeWeighted::combine_data(&key(b"btc_usd"), values, prev), prev);
+    });
+}
+
+#[test]
+fn stake_weighted_combine_only_weighs_the_given_values() {
+    new_test_ext().execute_with(|| {
+        Timestamp::set_timestamp(10_000);
+        for (operator, value) in [(alice(), 100), (bob(), 200), (charlie(), 300)] {
+            RawValues::<Test>::insert(
+                CreatorId::AccountId(operator),
+                key(b"btc_usd"),
+                TimestampedValue { value, timestamp: 10_000 },
+            );
+        }
+        Stakes::set(vec![(alice(), 1), (bob(), 1), (charlie(), 5)]);
+
+        // Charlie's heavy stake doesn't count once his value is filtered out by the caller.
+        let values = vec![
+            TimestampedValue { value: 100, timestamp: 10_000 },
+            TimestampedValue { value: 200, timestamp: 10_000 },
+        ];
+        assert_eq!(
+            StakeWeighted::combine_data(&key(b"btc_usd"), values, None).map(|v| v.value),
+            Some(100)
+        );
+        assert_eq!(StakeWeighted::combine_data(&key(b"btc_usd"), vec![], None), None);
+    });
+}
+
+#[test]
+fn forced_value_overrides_combine_until_cleared() {
+    new_test_ext().execute_with(|| {
+        Timestamp::set_timestamp(10_000);
+        assert_noop!(
+            KylinOracle::force_feed_value(RuntimeOrigin::signed(alice()), key(b"btc_usd"), 500),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(KylinOracle::force_feed_value(RuntimeOrigin::root(), key(b"btc_usd"), 500));
+        System::assert_last_event(RuntimeEvent::KylinOracle(crate::Event::ValueForced {
+            key: key(b"btc_usd"),
+            value: TimestampedValue { value: 500, timestamp: 10_000 },
+        }));
+
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")).map(|v| v.value), Some(500));
+
+        assert_ok!(KylinOracle::clear_forced_value(RuntimeOrigin::root(), key(b"btc_usd")));
+        assert!(!KylinOracle::forced_values(key(b"btc_usd")));
+        assert_noop!(
+            KylinOracle::clear_forced_value(RuntimeOrigin::root(), key(b"btc_usd")),
+            DispatchError::CannotLookup
+        );
+
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 102)]));
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")).map(|v| v.value), Some(102));
+    });
+}
+
+#[test]
+fn identical_combines_flag_feed_as_frozen() {
+    new_test_ext().execute_with(|| {
+        FrozenRounds::set(3);
+        SuppressFrozen::set(true);
+
+        // Each report is newer than the published value, so every feed is combined again.
+        for n in 0..3 {
+            Timestamp::set_timestamp(10_000 + n * 1_000);
+            assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+            KylinOracle::on_finalize(n);
+            assert!(!KylinOracle::is_frozen(&key(b"btc_usd")));
+        }
+        assert_eq!(KylinOracle::get_fresh(&key(b"btc_usd")).map(|v| v.value), Some(100));
+
+        Timestamp::set_timestamp(13_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        KylinOracle::on_finalize(3);
+        assert!(KylinOracle::is_frozen(&key(b"btc_usd")));
+        assert!(System::events().iter().any(|r| r.event
+            == RuntimeEvent::KylinOracle(crate::Event::FeedFrozen { key: key(b"btc_usd"), value: 100 })));
+        assert_eq!(KylinOracle::get_fresh(&key(b"btc_usd")), None);
+        assert_eq!(KylinOracle::get(&key(b"btc_usd")).map(|v| v.value), Some(100));
+
+        Timestamp::set_timestamp(14_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 101)]));
+        assert!(!KylinOracle::is_frozen(&key(b"btc_usd")));
+        assert!(System::events().iter().any(|r| r.event
+            == RuntimeEvent::KylinOracle(crate::Event::FeedUnfrozen { key: key(b"btc_usd") })));
+        assert_eq!(KylinOracle::get_fresh(&key(b"btc_usd")).map(|v| v.value), Some(101));
+    });
+}
+
+#[test]
+fn value_sink_receives_published_values_only() {
+    new_test_ext().execute_with(|| {
+        Timestamp::set_timestamp(10_000);
+        MinimumCount::set(2);
+        UpdateDeadband::set(10);
+
+        // No quorum yet, nothing is published.
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert!(PublishedValues::get().is_empty());
+
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 104)]));
+        assert_eq!(PublishedValues::get(), vec![(b"btc_usd".to_vec(), 104, 10_000)]);
+
+        // Within the dead-band, the raw value isn't stored and nothing is combined.
+        KylinOracle::on_finalize(1);
+        Timestamp::set_timestamp(11_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 105)]));
+        assert_eq!(PublishedValues::get().len(), 1);
+
+        assert_ok!(KylinOracle::force_feed_value(RuntimeOrigin::root(), key(b"btc_usd"), 500));
+        assert_eq!(PublishedValues::get().last(), Some(&(b"btc_usd".to_vec(), 500, 11_000)));
+    });
+}
+
+#[test]
+fn offchain_worker_does_nothing_at_genesis() {
+    const PHRASE: &str =
+        "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
+    let (offchain, _offchain_state) = testing::TestOffchainExt::new();
+    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+    let keystore = KeyStore::new();
+    SyncCryptoStore::sr25519_generate_new(
+        &keystore,
+        kylin_oracle::KEY_TYPE,
+        Some(&format!("{}/hunter1", PHRASE)),
+    )
+    .unwrap();
+
+    let mut t = new_test_ext();
+    t.register_extension(OffchainWorkerExt::new(offchain.clone()));
+    t.register_extension(OffchainDbExt::new(offchain));
+    t.register_extension(TransactionPoolExt::new(pool));
+    t.register_extension(KeystoreExt(Arc::new(keystore)));
+    t.execute_with(|| {
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"btc_usd"),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
+
+        // No request is expected, so a fetch would fail the test.
+        KylinOracle::offchain_worker(0);
+        assert!(pool_state.read().transactions.is_empty());
+    });
+}
+
+#[test]
+fn feed_config_exposes_feed_source() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"btc_usd"),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            Some(1),
+            Some(1_000_000_000),
+            None,
+            None,
+        ));
+
+        assert_eq!(
+            KylinOracle::feed_config(CreatorId::AccountId(alice()), &key(b"btc_usd")),
+            Some(ApiFeedInfo {
+                url: Some(b"https://api.kylin-node.co.uk/prices".to_vec()),
+                vpath: Some(b"/USD".to_vec()),
+                min: Some(1),
+                max: Some(1_000_000_000),
+                fetch_interval: 1,
+                decimals: VALUE_DECIMALS,
+                disabled: false,
+            })
+        );
+        assert_eq!(KylinOracle::feed_config(CreatorId::AccountId(bob()), &key(b"btc_usd")), None);
+        assert_eq!(KylinOracle::feed_config(CreatorId::AccountId(alice()), &key(b"eth_usd")), None);
+
+        // A boolean feed has no decimals, even before it published a value.
+        assert_ok!(KylinOracle::set_feed_boolean(RuntimeOrigin::signed(alice()), key(b"btc_usd"), true));
+        assert_eq!(
+            KylinOracle::feed_config(CreatorId::AccountId(alice()), &key(b"btc_usd")).unwrap().decimals,
+            0
+        );
+    });
+}
+
+/// Run the offchain worker at block 2 with the key of `alice()` and one due feed, then
+/// hand the single submitted transaction to `check`.
+fn with_submitted_feed(mode: SubmissionMode, check: impl FnOnce(Extrinsic)) {
+    let (offchain, offchain_state) = testing::TestOffchainExt::new();
+    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+    let keystore = KeyStore::new();
+    SyncCryptoStore::sr25519_generate_new(&keystore, kylin_oracle::KEY_TYPE, Some("//Alice"))
+        .unwrap();
+
+    let mut t = new_test_ext();
+    t.register_extension(OffchainWorkerExt::new(offchain.clone()));
+    t.register_extension(OffchainDbExt::new(offchain));
+    t.register_extension(TransactionPoolExt::new(pool));
+    t.register_extension(KeystoreExt(Arc::new(keystore)));
+    t.execute_with(|| {
+        FeedSubmissionMode::set(mode);
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"btc_usd"),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
+        offchain_state.write().expect_request(testing::PendingRequest {
+            method: "GET".into(),
+            uri: "https://api.kylin-node.co.uk/prices".into(),
+            response: Some(br#"{"USD": 155.23}"#.to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+
+        System::set_block_number(2);
+        KylinOracle::offchain_worker(2);
+        let tx = pool_state.write().transactions.pop().unwrap();
+        assert!(pool_state.read().transactions.is_empty());
+        check(Extrinsic::decode(&mut &*tx).unwrap());
+    });
+}
+
+#[test]
+fn signed_only_mode_feeds_signed_transactions() {
+    with_submitted_feed(SubmissionMode::SignedOnly, |tx| {
+        assert!(tx.signature.is_some());
+        assert_eq!(
+            tx.call,
+            RuntimeCall::KylinOracle(crate::Call::feed_data { values: vec![(key(b"btc_usd"), 155_230_000)] })
+        );
+    });
+}
+
+#[test]
+fn unsigned_only_mode_feeds_validated_unsigned_transactions() {
+    with_submitted_feed(SubmissionMode::UnsignedOnly, |tx| {
+        assert!(tx.signature.is_none());
+        let (payload, signature) = match tx.call {
+            RuntimeCall::KylinOracle(crate::Call::feed_data_unsigned { payload, signature }) => (payload, signature),
+            call => panic!("unexpected call {:?}", call),
+        };
+        assert_eq!(payload.public, alice());
+        assert_eq!(payload.block_number, 2);
+        let call = crate::Call::feed_data_unsigned { payload: payload.clone(), signature: signature.clone() };
+        assert_ok!(KylinOracle::validate_unsigned(TransactionSource::External, &call));
+
+        // A payload altered after signing doesn't pass.
+        let mut forged = payload.clone();
+        forged.values = vec![(key(b"btc_usd"), 1)];
+        assert_eq!(
+            KylinOracle::validate_unsigned(
+                TransactionSource::External,
+                &crate::Call::feed_data_unsigned { payload: forged, signature: signature.clone() },
+            ),
+            InvalidTransaction::BadProof.into()
+        );
+
+        assert_ok!(KylinOracle::feed_data_unsigned(RuntimeOrigin::none(), payload.clone(), signature.clone()));
+        assert_eq!(
+            KylinOracle::raw_values(CreatorId::AccountId(alice()), key(b"btc_usd")).map(|v| v.value),
+            Some(155_230_000)
+        );
+        assert_eq!(KylinOracle::last_unsigned_feed(alice()), Some(2));
+
+        // Once accepted, the payload can't be replayed, not even in a later block.
+        KylinOracle::on_finalize(2);
+        System::set_block_number(3);
+        assert_eq!(
+            KylinOracle::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Stale.into()
+        );
+        assert_noop!(
+            KylinOracle::feed_data_unsigned(RuntimeOrigin::none(), payload, signature),
+            Error::<Test>::StalePayload
+        );
+    });
+}
+
+#[test]
+fn either_mode_feeds_signed_and_accepts_unsigned() {
+    with_submitted_feed(SubmissionMode::UnsignedOnly, |tx| {
+        let call = match tx.call {
+            RuntimeCall::KylinOracle(call) => call,
+            call => panic!("unexpected call {:?}", call),
+        };
+        FeedSubmissionMode::set(SubmissionMode::Either);
+        assert_ok!(KylinOracle::validate_unsigned(TransactionSource::External, &call));
+        FeedSubmissionMode::set(SubmissionMode::SignedOnly);
+        assert_eq!(
+            KylinOracle::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Call.into()
+        );
+    });
+
+    with_submitted_feed(SubmissionMode::Either, |tx| {
+        assert!(tx.signature.is_some());
+        assert!(matches!(tx.call, RuntimeCall::KylinOracle(crate::Call::feed_data { .. })));
+    });
+}
+
+#[test]
+fn combine_strategy_is_selected_per_key() {
+    new_test_ext().execute_with(|| {
+        let strategies = [
+            (key(b"median"), CombineStrategy::Median),
+            (key(b"mean"), CombineStrategy::Mean),
+            (key(b"latest"), CombineStrategy::Latest),
+            (key(b"twap"), CombineStrategy::Twap),
+        ];
+        assert_noop!(
+            KylinOracle::set_combine_strategy(RuntimeOrigin::signed(alice()), key(b"mean"), Some(CombineStrategy::Mean)),
+            DispatchError::BadOrigin
+        );
+        for (k, strategy) in strategies.iter() {
+            assert_ok!(KylinOracle::set_combine_strategy(RuntimeOrigin::root(), k.clone(), Some(*strategy)));
+        }
+        System::assert_last_event(RuntimeEvent::KylinOracle(crate::Event::CombineStrategySet {
+            key: key(b"twap"),
+            strategy: Some(CombineStrategy::Twap),
+        }));
+
+        for (who, at, value) in [(alice(), 10_000, 100), (bob(), 11_000, 110), (charlie(), 12_000, 130)] {
+            Timestamp::set_timestamp(at);
+            let values = strategies.iter().map(|(k, _)| (k.clone(), value)).collect();
+            assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(who), values));
+        }
+
+        let value_of = |name: &[u8]| KylinOracle::get(&key(name)).map(|v| v.value);
+        assert_eq!(value_of(b"median"), Some(110));
+        assert_eq!(value_of(b"mean"), Some(113));
+        assert_eq!(value_of(b"latest"), Some(130));
+        // 100 and 110 were each the latest report for one second, 130 only just came in.
+        assert_eq!(value_of(b"twap"), Some(105));
+
+        assert_ok!(KylinOracle::set_combine_strategy(RuntimeOrigin::root(), key(b"mean"), None));
+        assert_eq!(KylinOracle::combine_strategies(key(b"mean")), None);
+    });
+}
+
+#[test]
+fn full_dispatch_set_is_not_reported_as_already_fed() {
+    new_test_ext().execute_with(|| {
+        MaxHasDispatchedSize::set(2);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 101)]));
+
+        assert_noop!(
+            KylinOracle::feed_data(RuntimeOrigin::signed(charlie()), vec![(key(b"btc_usd"), 102)]),
+            Error::<Test>::DispatchSetFull
+        );
+        assert_noop!(
+            KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]),
+            Error::<Test>::AlreadyFeeded
+        );
+
+        KylinOracle::on_finalize(1);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(charlie()), vec![(key(b"btc_usd"), 102)]));
+    });
+}
+
+#[test]
+fn migration_to_v2_keeps_feeds_enabled() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+    use frame_support::BoundedVec;
+    use migrations::v2::OldApiFeed;
+
+    new_test_ext().execute_with(|| {
+        StorageVersion::new(1).put::<KylinOracle>();
+        let old_feed: OldApiFeed<u64, BoundedVec<u8, ConstU32<64>>, BoundedVec<u8, ConstU32<16>>> = OldApiFeed {
+            requested_block_number: 7,
+            url: Some(b"https://api.kylin-node.co.uk/prices".to_vec().try_into().unwrap()),
+            vpath: Some(b"/USD".to_vec().try_into().unwrap()),
+            min: Some(1),
+            max: None,
+        };
+        frame_support::storage::unhashed::put(
+            &ApiFeeds::<Test>::hashed_key_for(CreatorId::AccountId(alice()), key(b"btc_usd")),
+            &old_feed,
+        );
+
+        migrations::v2::migrate::<Test>();
+
+        let feed = KylinOracle::api_feeds(CreatorId::AccountId(alice()), key(b"btc_usd")).unwrap();
+        assert_eq!(feed.requested_block_number, 7);
+        assert_eq!((feed.min, feed.max), (Some(1), None));
+        assert!(!feed.disabled);
+        assert_eq!(KylinOracle::on_chain_storage_version(), 2);
+    });
+}
+
+#[test]
+fn migration_to_v3_records_an_owner_per_key() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        let feed = ApiFeed {
+            requested_block_number: 7,
+            url: Some(b"https://api.kylin-node.co.uk/prices".to_vec().try_into().unwrap()),
+            vpath: Some(b"/USD".to_vec().try_into().unwrap()),
+            min: Some(1),
+            max: None,
+            disabled: false,
+        };
+        ApiFeeds::<Test>::insert(CreatorId::AccountId(alice()), key(b"btc_usd"), feed.clone());
+        ApiFeeds::<Test>::insert(CreatorId::AccountId(bob()), key(b"btc_usd"), feed.clone());
+        ApiFeeds::<Test>::insert(CreatorId::AccountId(bob()), key(b"eth_usd"), feed);
+        StorageVersion::new(2).put::<KylinOracle>();
+
+        migrations::v3::migrate::<Test>();
+
+        let owner = KylinOracle::feed_owners(key(b"btc_usd")).unwrap();
+        assert!(owner == CreatorId::AccountId(alice()) || owner == CreatorId::AccountId(bob()));
+        assert_eq!(KylinOracle::feed_owners(key(b"eth_usd")), Some(CreatorId::AccountId(bob())));
+        assert_eq!(KylinOracle::on_chain_storage_version(), 3);
+    });
+}
+
+#[test]
+fn migration_to_v4_counts_raw_values() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        let value = TimestampedValue { value: 42, timestamp: 1_000 };
+        RawValues::<Test>::insert(CreatorId::AccountId(alice()), key(b"btc_usd"), value);
+        RawValues::<Test>::insert(CreatorId::AccountId(bob()), key(b"btc_usd"), value);
+        RawValues::<Test>::insert(CreatorId::AccountId(bob()), key(b"eth_usd"), value);
+        StorageVersion::new(3).put::<KylinOracle>();
+
+        KylinOracle::on_runtime_upgrade();
+
+        assert_eq!(KylinOracle::raw_value_count(), 3);
+        assert_eq!(KylinOracle::on_chain_storage_version(), KylinOracle::current_storage_version());
+
+        // A feed replacing its raw value doesn't count it again.
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 43)]));
+        assert_eq!(KylinOracle::raw_value_count(), 3);
+    });
+}
+
+#[test]
+fn migration_to_v5_clears_fetch_health_per_operator() {
+    use frame_support::traits::{GetStorageVersion, StorageVersion};
+
+    new_test_ext().execute_with(|| {
+        let health = FeedHealth { consecutive_failures: 3, ..Default::default() };
+        FeedHealths::<Test>::insert(CreatorId::AccountId(bob()), key(b"btc_usd"), health.clone());
+        FeedHealths::<Test>::insert(CreatorId::AccountId(charlie()), key(b"btc_usd"), health);
+        StorageVersion::new(4).put::<KylinOracle>();
+
+        KylinOracle::on_runtime_upgrade();
+
+        assert_eq!(FeedHealths::<Test>::iter().count(), 0);
+        assert_eq!(KylinOracle::on_chain_storage_version(), 5);
+    });
+}
+
+#[test]
+fn consecutive_fetch_failures_auto_disable_feed() {
+    new_test_ext().execute_with(|| {
+        MaxConsecutiveFailures::set(2);
+        FetchFailureQuorum::set(2);
+        let btc = key(b"btc_usd");
+        let alice_feed = CreatorId::AccountId(alice());
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            btc.clone(),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
+        let report_failure = |who: AccountId, now: u64| {
+            Timestamp::set_timestamp(now);
+            assert_ok!(KylinOracle::report_fetch_errors(
+                RuntimeOrigin::signed(who),
+                vec![(alice_feed.clone(), btc.clone(), FetchErrorKind::Http)],
+            ));
+        };
+        let health = || KylinOracle::feed_health(&alice_feed, &btc);
+        let is_disabled = || KylinOracle::api_feeds(&alice_feed, &btc).unwrap().disabled;
+
+        // Repeating a report within the block doesn't count.
+        report_failure(alice(), 1_000);
+        report_failure(alice(), 1_000);
+        assert_eq!(health().consecutive_failures, 1);
+        // Failures of a feed that doesn't exist are ignored.
+        assert_ok!(KylinOracle::report_fetch_errors(
+            RuntimeOrigin::signed(alice()),
+            vec![(CreatorId::AccountId(bob()), btc.clone(), FetchErrorKind::Http)],
+        ));
+        assert_eq!(health().consecutive_failures, 1);
+        assert!(!FeedHealths::<Test>::contains_key(CreatorId::AccountId(bob()), &btc));
+
+        // A single operator reporting every failure isn't a quorum.
+        report_failure(alice(), 2_000);
+        assert_eq!(health().consecutive_failures, 2);
+        assert!(!is_disabled());
+        assert_noop!(
+            KylinOracle::enable_feed(RuntimeOrigin::signed(alice()), btc.clone()),
+            Error::<Test>::FeedNotDisabled
+        );
+
+        // Another operator confirming the failure within the block completes the quorum.
+        report_failure(bob(), 2_000);
+        assert_eq!(health().consecutive_failures, 2);
+        assert_eq!(health().reporters, vec![alice(), bob()]);
+        assert!(is_disabled());
+        System::assert_last_event(RuntimeEvent::KylinOracle(crate::Event::FeedAutoDisabled {
+            creator: alice_feed.clone(),
+            key: btc.clone(),
+            failures: 2,
+        }));
+        assert!(KylinOracle::feed_config(alice_feed.clone(), &btc).unwrap().disabled);
+
+        // Only the creator can re-enable the feed.
+        assert_noop!(
+            KylinOracle::enable_feed(RuntimeOrigin::signed(bob()), btc.clone()),
+            DispatchError::CannotLookup
+        );
+        assert_ok!(KylinOracle::enable_feed(RuntimeOrigin::signed(alice()), btc.clone()));
+        assert!(!is_disabled());
+        System::assert_last_event(RuntimeEvent::KylinOracle(crate::Event::FeedEnabled {
+            sender: alice_feed.clone(),
+            key: btc.clone(),
+        }));
+        assert_eq!(health().consecutive_failures, 0);
+        assert!(health().reporters.is_empty());
+
+        // The reporters of the previous failures don't count towards the next quorum.
+        report_failure(alice(), 4_000);
+        report_failure(alice(), 5_000);
+        assert_eq!(health().consecutive_failures, 2);
+        assert!(!is_disabled());
+    });
+}
+
+#[test]
+fn offchain_sweep_skips_disabled_feed() {
+    const PHRASE: &str =
+        "news slush supreme milk chapter athlete soap sausage put clutch what kitten";
+    let (offchain, offchain_state) = testing::TestOffchainExt::new();
+    let (pool, pool_state) = testing::TestTransactionPoolExt::new();
+    let keystore = KeyStore::new();
+    SyncCryptoStore::sr25519_generate_new(
+        &keystore,
+        kylin_oracle::KEY_TYPE,
+        Some(&format!("{}/hunter1", PHRASE)),
+    )
+    .unwrap();
+
+    let mut t = new_test_ext();
+    t.register_extension(OffchainWorkerExt::new(offchain.clone()));
+    t.register_extension(OffchainDbExt::new(offchain));
+    t.register_extension(TransactionPoolExt::new(pool));
+    t.register_extension(KeystoreExt(Arc::new(keystore)));
+    t.execute_with(|| {
+        MaxConsecutiveFailures::set(1);
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"btc_usd"),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_ok!(KylinOracle::report_fetch_errors(
+            RuntimeOrigin::signed(bob()),
+            vec![(CreatorId::AccountId(alice()), key(b"btc_usd"), FetchErrorKind::Http)],
+        ));
+
+        // No request is expected, so a fetch would fail the test.
+        KylinOracle::offchain_worker(2);
+        assert_eq!(pool_state.read().transactions.len(), 0);
+
+        assert_ok!(KylinOracle::enable_feed(RuntimeOrigin::signed(alice()), key(b"btc_usd")));
+        offchain_state.write().expect_request(testing::PendingRequest {
+            method: "GET".into(),
+            uri: "https://api.kylin-node.co.uk/prices".into(),
+            response: Some(br#"{"USD": 155.23}"#.to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+        KylinOracle::offchain_worker(3);
+        assert_eq!(pool_state.read().transactions.len(), 1);
+    });
+}
+
+#[test]
+fn snapshot_exports_feeds_values_and_raws_sorted_by_key() {
+    new_test_ext().execute_with(|| {
+        for (who, name) in [(bob(), &b"sol_usd"[..]), (alice(), &b"eth_usd"[..]), (alice(), &b"btc_usd"[..])] {
+            assert_ok!(KylinOracle::submit_api(
+                RuntimeOrigin::signed(who),
+                key(name),
+                b"https://api.kylin-node.co.uk/prices".to_vec(),
+                b"/USD".to_vec(),
+                None,
+                None,
+                None,
+                None,
+            ));
+        }
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(
+            RuntimeOrigin::signed(alice()),
+            vec![(key(b"sol_usd"), 30), (key(b"btc_usd"), 100), (key(b"eth_usd"), 20)],
+        ));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 300)]));
+
+        let feed = |who: AccountId, name: &[u8]| {
+            let feed = KylinOracle::api_feeds(CreatorId::AccountId(who.clone()), key(name)).unwrap();
+            (CreatorId::AccountId(who), name.to_vec(), feed)
+        };
+        let value = |name: &[u8]| (name.to_vec(), KylinOracle::values(key(name)).unwrap());
+        let raw = |who: AccountId, name: &[u8]| {
+            let raw = KylinOracle::raw_values(CreatorId::AccountId(who.clone()), key(name)).unwrap();
+            (CreatorId::AccountId(who), name.to_vec(), raw)
+        };
+        let mut btc_raws = vec![raw(alice(), b"btc_usd"), raw(bob(), b"btc_usd")];
+        btc_raws.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let snapshot = KylinOracle::snapshot(None, 10);
+        assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+        assert_eq!(
+            snapshot.feeds,
+            vec![feed(alice(), b"btc_usd"), feed(alice(), b"eth_usd"), feed(bob(), b"sol_usd")]
+        );
+        assert_eq!(snapshot.values, vec![value(b"btc_usd"), value(b"eth_usd"), value(b"sol_usd")]);
+        let mut raws = btc_raws.clone();
+        raws.extend([raw(alice(), b"eth_usd"), raw(alice(), b"sol_usd")]);
+        assert_eq!(snapshot.raw_values, raws);
+        assert_eq!(snapshot.next, None);
+
+        let first = KylinOracle::snapshot(None, 2);
+        assert_eq!(first.feeds, vec![feed(alice(), b"btc_usd"), feed(alice(), b"eth_usd")]);
+        assert_eq!(first.values, vec![value(b"btc_usd"), value(b"eth_usd")]);
+        assert_eq!(first.raw_values.len(), 3);
+        assert_eq!(first.next, Some(b"eth_usd".to_vec()));
+
+        let second = KylinOracle::snapshot(first.next, 2);
+        assert_eq!(second.feeds, vec![feed(bob(), b"sol_usd")]);
+        assert_eq!(second.values, vec![value(b"sol_usd")]);
+        assert_eq!(second.raw_values, vec![raw(alice(), b"sol_usd")]);
+        assert_eq!(second.next, None);
+    });
+}
+
+#[test]
+fn deregister_operator_removes_only_the_callers_feeds_and_raws() {
+    let alice_cid = CreatorId::AccountId(alice());
+    let bob_cid = CreatorId::AccountId(bob());
+    // `clear_prefix` only honours its limit for committed storage.
+    let mut t = new_test_ext();
+    t.execute_with(|| {
+        for (who, name) in [(alice(), &b"btc_usd"[..]), (alice(), &b"eth_usd"[..]), (bob(), &b"btc_usd"[..])] {
+            assert_ok!(KylinOracle::submit_api(
+                RuntimeOrigin::signed(who),
+                key(name),
+                b"https://api.kylin-node.co.uk/prices".to_vec(),
+                b"/USD".to_vec(),
+                None,
+                None,
+                None,
+                None,
+            ));
+        }
+        assert_ok!(KylinOracle::set_feed_boolean(RuntimeOrigin::signed(alice()), key(b"eth_usd"), true));
+        Timestamp::set_timestamp(10_000);
+        let values = vec![(key(b"btc_usd"), 100), (key(b"eth_usd"), 20), (key(b"sol_usd"), 30)];
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), values.clone()));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), values));
+        assert_eq!(KylinOracle::raw_value_count(), 6);
+        assert_eq!(FeedHealths::<Test>::iter_prefix(&alice_cid).count(), 2);
+    });
+    t.commit_all().unwrap();
+
+    t.execute_with(|| {
+        assert_ok!(KylinOracle::deregister_operator(RuntimeOrigin::signed(alice()), 2));
+        System::assert_last_event(RuntimeEvent::KylinOracle(crate::Event::OperatorDeregistered {
+            operator: alice_cid.clone(),
+            feeds_removed: 2,
+            raw_values_removed: 2,
+        }));
+        assert!(KylinOracle::feeds_of(alice_cid.clone()).is_empty());
+        assert!(!KylinOracle::boolean_feeds(&alice_cid, key(b"eth_usd")));
+        assert_eq!(RawValues::<Test>::iter_prefix(&alice_cid).count(), 1);
+        assert_eq!(KylinOracle::raw_value_count(), 4);
+        assert_eq!(FeedHealths::<Test>::iter_prefix(&alice_cid).count(), 0);
+    });
+    t.commit_all().unwrap();
+
+    // The remaining raw value is cleared by another call.
+    t.execute_with(|| {
+        assert_ok!(KylinOracle::deregister_operator(RuntimeOrigin::signed(alice()), 2));
+        System::assert_last_event(RuntimeEvent::KylinOracle(crate::Event::OperatorDeregistered {
+            operator: alice_cid.clone(),
+            feeds_removed: 0,
+            raw_values_removed: 1,
+        }));
+        assert_eq!(RawValues::<Test>::iter_prefix(&alice_cid).count(), 0);
+
+        assert_eq!(KylinOracle::feeds_of(bob_cid.clone()).len(), 1);
+        assert_eq!(RawValues::<Test>::iter_prefix(&bob_cid).count(), 3);
+        assert_eq!(KylinOracle::raw_value_count(), 3);
+    });
+}
+
+#[test]
+fn dispersion_event_reports_spread_of_fresh_raws() {
+    new_test_ext().execute_with(|| {
+        let dispersions = || {
+            System::events()
+                .into_iter()
+                .filter_map(|r| match r.event {
+                    RuntimeEvent::KylinOracle(crate::Event::FeedDispersion { spread, .. }) => Some(spread),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        assert!(dispersions().is_empty());
+
+        EmitDispersion::set(true);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 130)]));
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(charlie()), vec![(key(b"btc_usd"), 90)]));
+        assert_eq!(dispersions(), vec![30, 40]);
+        System::assert_has_event(RuntimeEvent::KylinOracle(crate::Event::FeedDispersion {
+            key: key(b"btc_usd"),
+            spread: 40,
+        }));
+
+        // Expired raw values no longer count towards the spread.
+        KylinOracle::on_finalize(1);
+        Timestamp::set_timestamp(80_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 105)]));
+        assert_eq!(dispersions(), vec![30, 40, 0]);
+        assert_eq!(KylinOracle::dispersion(&key(b"eth_usd")), None);
+    });
+}
+
+#[test]
+fn value_pair_tags_are_returned_with_the_value() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"btc_usd"),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            None,
+            None,
+            Some((b"BTC".to_vec(), b"USD".to_vec())),
+            None,
+        ));
+        assert_ok!(KylinOracle::submit_api(
+            RuntimeOrigin::signed(alice()),
+            key(b"eth_usd"),
+            b"https://api.kylin-node.co.uk/prices".to_vec(),
+            b"/USD".to_vec(),
+            None,
+            None,
+            None,
+            None,
+        ));
+        assert_noop!(
+            KylinOracle::submit_api(
+                RuntimeOrigin::signed(alice()),
+                key(b"sol_usd"),
+                b"https://api.kylin-node.co.uk/prices".to_vec(),
+                b"/USD".to_vec(),
+                None,
+                None,
+                Some((b"SOLANA-SOL".to_vec(), b"USD".to_vec())),
+                None,
+            ),
+            Error::<Test>::TooLarge
+        );
+
+        let pair = ValuePair {
+            base: b"BTC".to_vec().try_into().unwrap(),
+            quote: b"USD".to_vec().try_into().unwrap(),
+        };
+        assert_eq!(KylinOracle::value_pairs(key(b"btc_usd")), Some(pair.clone()));
+        assert_eq!(KylinOracle::get_with_pair(&key(b"btc_usd")), None);
+
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(
+            RuntimeOrigin::signed(alice()),
+            vec![(key(b"btc_usd"), 100), (key(b"eth_usd"), 20)],
+        ));
+
+        let value = |v| TimestampedValue { value: v, timestamp: 10_000 };
+        assert_eq!(KylinOracle::get_with_pair(&key(b"btc_usd")), Some((value(100), Some(pair))));
+        assert_eq!(KylinOracle::get_with_pair(&key(b"eth_usd")), Some((value(20), None)));
+        assert_eq!(KylinOracle::get(&key(b"eth_usd")), Some(value(20)));
+
+        assert_ok!(KylinOracle::xcm_query_data(sibling(2000), key(b"btc_usd")));
+        assert_eq!(
+            KylinMockCall::decode(&mut &sent_transact_call()[..]).unwrap(),
+            KylinMockCall::KylinFeed(KylinMockFunc::xcm_feed_back {
+                key: b"btc_usd".to_vec(),
+                value: 100,
+                decimals: VALUE_DECIMALS,
+                pair: Some((b"BTC".to_vec(), b"USD".to_vec())),
+                confidence: None,
+            })
+        );
+    });
+}
+
+#[test]
+fn value_pair_belongs_to_the_key_owner() {
+    new_test_ext().execute_with(|| {
+        let submit = |who: AccountId, pair: Option<(&[u8], &[u8])>| {
+            KylinOracle::submit_api(
+                RuntimeOrigin::signed(who),
+                key(b"btc_usd"),
+                b"https://api.kylin-node.co.uk/prices".to_vec(),
+                b"/USD".to_vec(),
+                None,
+                None,
+                pair.map(|(base, quote)| (base.to_vec(), quote.to_vec())),
+                None,
+            )
+        };
+        let pair = |base: &[u8], quote: &[u8]| ValuePair {
+            base: base.to_vec().try_into().unwrap(),
+            quote: quote.to_vec().try_into().unwrap(),
+        };
+        assert_ok!(submit(alice(), Some((b"BTC", b"USD"))));
+
+        // Another creator of the key can't retag it.
+        assert_noop!(submit(bob(), Some((b"BTC", b"EUR"))), Error::<Test>::NoPermission);
+        assert_ok!(submit(bob(), None));
+        assert_eq!(KylinOracle::value_pairs(key(b"btc_usd")), Some(pair(b"BTC", b"USD")));
+
+        // The pair moves along with the ownership.
+        assert_ok!(KylinOracle::transfer_feed(RuntimeOrigin::signed(alice()), key(b"btc_usd"), charlie()));
+        assert_noop!(submit(alice(), Some((b"BTC", b"EUR"))), Error::<Test>::NoPermission);
+        assert_eq!(KylinOracle::value_pairs(key(b"btc_usd")), Some(pair(b"BTC", b"USD")));
+
+        // Removing a feed that doesn't own the key leaves the pair alone.
+        assert_ok!(KylinOracle::remove_api(RuntimeOrigin::signed(bob()), key(b"btc_usd")));
+        assert_eq!(KylinOracle::value_pairs(key(b"btc_usd")), Some(pair(b"BTC", b"USD")));
+
+        assert_ok!(KylinOracle::remove_api(RuntimeOrigin::signed(charlie()), key(b"btc_usd")));
+        assert_eq!(KylinOracle::value_pairs(key(b"btc_usd")), None);
+    });
+}
+
+#[test]
+fn confidence_follows_operator_agreement() {
+    new_test_ext().execute_with(|| {
+        StoreConfidence::set(true);
+        Timestamp::set_timestamp(10_000);
+        for (who, btc, eth) in [(alice(), 1_000, 1_000), (bob(), 1_010, 1_500), (charlie(), 1_005, 700)] {
+            assert_ok!(KylinOracle::feed_data(
+                RuntimeOrigin::signed(who),
+                vec![(key(b"btc_usd"), btc), (key(b"eth_usd"), eth)],
+            ));
+        }
+
+        // 10 apart on a median of 1_005, and 800 apart on a median of 1_000.
+        let btc = KylinOracle::get_with_confidence(&key(b"btc_usd")).unwrap();
+        assert_eq!(btc.0.value, 1_005);
+        assert_eq!(btc.1, Some(Permill::from_parts(990_050)));
+        let eth = KylinOracle::get_with_confidence(&key(b"eth_usd")).unwrap();
+        assert_eq!(eth.0.value, 1_000);
+        assert_eq!(eth.1, Some(Permill::from_percent(20)));
+
+        // Query answers carry the confidence along with the value.
+        let answered_confidence = || {
+            SentXcm::set(vec![]);
+            assert_ok!(KylinOracle::xcm_query_data(sibling(2000), key(b"btc_usd")));
+            match KylinMockCall::decode(&mut &sent_transact_call()[..]).unwrap() {
+                KylinMockCall::KylinFeed(KylinMockFunc::xcm_feed_back { confidence, .. }) => confidence,
+            }
+        };
+        assert_eq!(answered_confidence(), Some(Permill::from_parts(990_050)));
+
+        assert_ok!(KylinOracle::force_feed_value(RuntimeOrigin::root(), key(b"btc_usd"), 2_000));
+        assert_eq!(KylinOracle::get_with_confidence(&key(b"btc_usd")).unwrap().1, None);
+        assert_eq!(answered_confidence(), None);
+
+        // Only the raw values of the source group combined count towards the confidence.
+        assert_ok!(KylinOracle::set_source_groups(
+            RuntimeOrigin::root(),
+            key(b"eth_usd"),
+            vec![vec![CreatorId::AccountId(alice()), CreatorId::AccountId(charlie())]],
+        ));
+        KylinOracle::on_finalize(1);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"eth_usd"), 990)]));
+        let eth = KylinOracle::get_with_confidence(&key(b"eth_usd")).unwrap();
+        assert_eq!(eth.0.value, 990);
+        assert_eq!(eth.1, Some(Permill::from_parts(707_071)));
+
+        StoreConfidence::set(false);
+        KylinOracle::on_finalize(2);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"sol_usd"), 30)]));
+        assert_eq!(KylinOracle::get_with_confidence(&key(b"sol_usd")).unwrap().1, None);
+    });
+}
+
+#[test]
+fn allowlisted_key_only_accepts_listed_operators() {
+    new_test_ext().execute_with(|| {
+        let submit = |who: AccountId, name: &[u8], allowlist: Option<Vec<CreatorId<AccountId>>>| {
+            KylinOracle::submit_api(
+                RuntimeOrigin::signed(who),
+                key(name),
+                b"https://api.kylin-node.co.uk/prices".to_vec(),
+                b"/USD".to_vec(),
+                None,
+                None,
+                None,
+                allowlist,
+            )
+        };
+        let premium = vec![CreatorId::AccountId(alice()), CreatorId::ParaId(2000.into())];
+        assert_noop!(
+            submit(alice(), b"btc_usd", Some(vec![CreatorId::AccountId(bob()); 3])),
+            Error::<Test>::TooManyAllowedOperators
+        );
+        assert_ok!(submit(alice(), b"btc_usd", Some(premium.clone())));
+        assert_ok!(submit(alice(), b"eth_usd", None));
+        // Another creator can register the key, but not lift or replace its allowlist.
+        assert_noop!(
+            submit(bob(), b"btc_usd", Some(vec![CreatorId::AccountId(bob())])),
+            Error::<Test>::NoPermission
+        );
+        assert_ok!(submit(bob(), b"btc_usd", None));
+        assert_eq!(KylinOracle::feed_allowlist(key(b"btc_usd")).unwrap().1.into_inner(), premium);
+
+        Timestamp::set_timestamp(10_000);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(alice()), vec![(key(b"btc_usd"), 100)]));
+        // Only the value of the restricted key is dropped, the rest of the batch is fed.
+        assert_ok!(KylinOracle::feed_data(
+            RuntimeOrigin::signed(bob()),
+            vec![(key(b"eth_usd"), 20), (key(b"btc_usd"), 110)],
+        ));
+        assert_eq!(
+            last_feed_statuses(),
+            vec![
+                (key(b"eth_usd"), 20, FeedStatus::Published),
+                (key(b"btc_usd"), 110, FeedStatus::NotAllowlisted),
+            ]
+        );
+        assert_eq!(KylinOracle::raw_values(CreatorId::AccountId(bob()), key(b"btc_usd")), None);
+        assert_ok!(KylinOracle::xcm_feed_data(sibling(2000), vec![(key(b"btc_usd"), 105)], None));
+        assert_ok!(KylinOracle::xcm_feed_data(sibling(2001), vec![(key(b"btc_usd"), 105)], None));
+        assert_eq!(last_feed_statuses(), vec![(key(b"btc_usd"), 105, FeedStatus::NotAllowlisted)]);
+        assert_eq!(KylinOracle::raw_values(CreatorId::ParaId(2001.into()), key(b"btc_usd")), None);
+
+        // Removing the feed that set the allowlist opens the key to every member again.
+        assert_ok!(KylinOracle::remove_api(RuntimeOrigin::signed(alice()), key(b"btc_usd")));
+        assert_eq!(KylinOracle::feed_allowlist(key(b"btc_usd")), None);
+        KylinOracle::on_finalize(1);
+        assert_ok!(KylinOracle::feed_data(RuntimeOrigin::signed(bob()), vec![(key(b"btc_usd"), 110)]));
+    });
+}
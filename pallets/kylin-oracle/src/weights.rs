@@ -30,10 +30,22 @@ use sp_std::marker::PhantomData;
 /// Weight functions needed for kylin_oracle.
 pub trait WeightInfo {
     fn query_data() -> Weight;
-    fn feed_data(c: u32) -> Weight;
+    fn feed_data(c: u32, r: u32) -> Weight;
     fn on_finalize() -> Weight;
-    fn submit_api() -> Weight;
+    fn retry_pending_responses(c: u32) -> Weight;
+    fn submit_api(f: u32) -> Weight;
+    fn submit_api_batch(c: u32, f: u32) -> Weight;
     fn remove_api() -> Weight;
+    fn transfer_feed() -> Weight;
+    fn set_feed_boolean() -> Weight;
+    fn set_source_groups(c: u32, s: u32) -> Weight;
+    fn report_fetch_errors(c: u32) -> Weight;
+    fn set_fetching_enabled() -> Weight;
+    fn force_feed_value() -> Weight;
+    fn clear_forced_value() -> Weight;
+    fn set_combine_strategy() -> Weight;
+    fn enable_feed() -> Weight;
+    fn deregister_operator(f: u32, r: u32) -> Weight;
 }
 
 /// Weights for kylin_oracle using the Substrate node and recommended hardware.
@@ -44,27 +56,117 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
             .saturating_add(T::DbWeight::get().reads(4 as u64))
             .saturating_add(T::DbWeight::get().writes(2 as u64))
     }
-    fn feed_data(c: u32, ) -> Weight {
+    fn feed_data(c: u32, r: u32, ) -> Weight {
         Weight::from_ref_time(16_800_000)
 			// Standard Error: 84_000
 			.saturating_add(Weight::from_ref_time(3_600_000).saturating_mul(c as u64))
-			.saturating_add(T::DbWeight::get().reads(3 as u64))
-			.saturating_add(T::DbWeight::get().writes(1 as u64))
-			.saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(c as u64)))
+			.saturating_add(Weight::from_ref_time(400_000).saturating_mul((c as u64).saturating_mul(r as u64)))
+			// Members, HasDispatched, Timestamp, and LastUnsignedFeed or FeedNonces
+			.saturating_add(T::DbWeight::get().reads(4 as u64))
+			.saturating_add(T::DbWeight::get().writes(2 as u64))
+			// Per value: allowlist, bounds, raw value and its count, owner and its health,
+			// combine inputs, previous value, unchanged rounds and decimals
+			.saturating_add(T::DbWeight::get().reads((18 as u64).saturating_mul(c as u64)))
+			// Per value: RawValues iterated by dispersion, combine and `CombineData`
+			.saturating_add(T::DbWeight::get().reads((3 as u64).saturating_mul(c as u64).saturating_mul(r as u64)))
+			// Per value: health, raw value and its count, value, decimals, confidence and
+			// unchanged rounds
+			.saturating_add(T::DbWeight::get().writes((7 as u64).saturating_mul(c as u64)))
 	}
 	fn on_finalize() -> Weight {
         Weight::from_ref_time(3_000_000)
 			.saturating_add(T::DbWeight::get().writes(1 as u64))
 	}
-    fn submit_api() -> Weight {
+	fn retry_pending_responses(c: u32, ) -> Weight {
+		Weight::from_ref_time(3_000_000)
+			.saturating_add(Weight::from_ref_time(121_180_000).saturating_mul(c as u64))
+			.saturating_add(T::DbWeight::get().reads(1 as u64))
+			.saturating_add(T::DbWeight::get().writes(1 as u64))
+	}
+    fn submit_api(f: u32, ) -> Weight {
         Weight::from_ref_time(66_168_000)
+            .saturating_add(Weight::from_ref_time(1_000_000).saturating_mul(f as u64))
             .saturating_add(T::DbWeight::get().reads(3 as u64))
             .saturating_add(T::DbWeight::get().writes(3 as u64))
+            // Counting the creator's feeds against `MaxFeedsPerCreator`
+            .saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(f as u64)))
+    }
+    fn submit_api_batch(c: u32, f: u32, ) -> Weight {
+        Weight::from_ref_time(66_168_000)
+            .saturating_add(Weight::from_ref_time(66_168_000).saturating_mul(c as u64))
+            .saturating_add(Weight::from_ref_time(1_000_000).saturating_mul((c as u64).saturating_mul(f as u64)))
+            .saturating_add(T::DbWeight::get().reads((3 as u64).saturating_mul(c as u64)))
+            .saturating_add(T::DbWeight::get().writes((3 as u64).saturating_mul(c as u64)))
+            // Counting the creator's feeds against `MaxFeedsPerCreator` for each entry
+            .saturating_add(T::DbWeight::get().reads((c as u64).saturating_mul(f as u64)))
     }
     fn remove_api() -> Weight {
         Weight::from_ref_time(66_168_000)
             .saturating_add(T::DbWeight::get().reads(3 as u64))
-            .saturating_add(T::DbWeight::get().writes(3 as u64))
+            .saturating_add(T::DbWeight::get().writes(4 as u64))
+    }
+    fn transfer_feed() -> Weight {
+        Weight::from_ref_time(66_168_000)
+            .saturating_add(T::DbWeight::get().reads(6 as u64))
+            .saturating_add(T::DbWeight::get().writes(7 as u64))
+    }
+    fn set_feed_boolean() -> Weight {
+        Weight::from_ref_time(30_000_000)
+            .saturating_add(T::DbWeight::get().reads(1 as u64))
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
+    fn set_source_groups(c: u32, s: u32, ) -> Weight {
+        Weight::from_ref_time(15_000_000)
+            // Bounding and encoding every group and its sources
+            .saturating_add(Weight::from_ref_time(1_000_000).saturating_mul(c as u64))
+            .saturating_add(Weight::from_ref_time(200_000).saturating_mul(s as u64))
+            // SourceGroups
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
+    fn report_fetch_errors(c: u32, ) -> Weight {
+        Weight::from_ref_time(15_000_000)
+            .saturating_add(Weight::from_ref_time(5_000_000).saturating_mul(c as u64))
+            // Members
+            .saturating_add(T::DbWeight::get().reads(1 as u64))
+            // Per error: feed and its health, and the feed again when it's disabled
+            .saturating_add(T::DbWeight::get().reads((3 as u64).saturating_mul(c as u64)))
+            .saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(c as u64)))
+    }
+    fn set_fetching_enabled() -> Weight {
+        Weight::from_ref_time(15_000_000)
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
+    fn force_feed_value() -> Weight {
+        Weight::from_ref_time(20_000_000)
+            // Owner of the key and whether its feed is boolean
+            .saturating_add(T::DbWeight::get().reads(2 as u64))
+            // Value, decimals, confidence and the forced flag
+            .saturating_add(T::DbWeight::get().writes(4 as u64))
+    }
+    fn clear_forced_value() -> Weight {
+        Weight::from_ref_time(15_000_000)
+            // ForcedValues
+            .saturating_add(T::DbWeight::get().reads(1 as u64))
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
+    fn set_combine_strategy() -> Weight {
+        Weight::from_ref_time(15_000_000)
+            // CombineStrategies
+            .saturating_add(T::DbWeight::get().writes(1 as u64))
+    }
+    fn enable_feed() -> Weight {
+        Weight::from_ref_time(20_000_000)
+            .saturating_add(T::DbWeight::get().reads(3 as u64))
+            .saturating_add(T::DbWeight::get().writes(2 as u64))
+    }
+    fn deregister_operator(f: u32, r: u32, ) -> Weight {
+        Weight::from_ref_time(20_000_000)
+            .saturating_add(Weight::from_ref_time(10_000_000).saturating_mul(f as u64))
+            .saturating_add(Weight::from_ref_time(5_000_000).saturating_mul(r as u64))
+            .saturating_add(T::DbWeight::get().reads_writes(1 as u64, 1 as u64))
+            .saturating_add(T::DbWeight::get().reads((1 as u64).saturating_mul(f as u64)))
+            .saturating_add(T::DbWeight::get().writes((3 as u64).saturating_mul(f as u64)))
+            .saturating_add(T::DbWeight::get().writes((2 as u64).saturating_mul(r as u64)))
     }
 }
 
@@ -75,26 +177,116 @@ impl WeightInfo for () {
             .saturating_add(RocksDbWeight::get().reads(4 as u64))
             .saturating_add(RocksDbWeight::get().writes(2 as u64))
     }
-    fn feed_data(c: u32, ) -> Weight {
+    fn feed_data(c: u32, r: u32, ) -> Weight {
 		Weight::from_ref_time(16_800_000)
 			// Standard Error: 84_000
 			.saturating_add(Weight::from_ref_time(3_600_000).saturating_mul(c as u64))
-			.saturating_add(RocksDbWeight::get().reads(3 as u64))
-			.saturating_add(RocksDbWeight::get().writes(1 as u64))
-			.saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(c as u64)))
+			.saturating_add(Weight::from_ref_time(400_000).saturating_mul((c as u64).saturating_mul(r as u64)))
+			// Members, HasDispatched, Timestamp, and LastUnsignedFeed or FeedNonces
+			.saturating_add(RocksDbWeight::get().reads(4 as u64))
+			.saturating_add(RocksDbWeight::get().writes(2 as u64))
+			// Per value: allowlist, bounds, raw value and its count, owner and its health,
+			// combine inputs, previous value, unchanged rounds and decimals
+			.saturating_add(RocksDbWeight::get().reads((18 as u64).saturating_mul(c as u64)))
+			// Per value: RawValues iterated by dispersion, combine and `CombineData`
+			.saturating_add(RocksDbWeight::get().reads((3 as u64).saturating_mul(c as u64).saturating_mul(r as u64)))
+			// Per value: health, raw value and its count, value, decimals, confidence and
+			// unchanged rounds
+			.saturating_add(RocksDbWeight::get().writes((7 as u64).saturating_mul(c as u64)))
 	}
 	fn on_finalize() -> Weight {
 		Weight::from_ref_time(3_000_000)
 			.saturating_add(RocksDbWeight::get().writes(1 as u64))
 	}
-    fn submit_api() -> Weight {
+	fn retry_pending_responses(c: u32, ) -> Weight {
+		Weight::from_ref_time(3_000_000)
+			.saturating_add(Weight::from_ref_time(121_180_000).saturating_mul(c as u64))
+			.saturating_add(RocksDbWeight::get().reads(1 as u64))
+			.saturating_add(RocksDbWeight::get().writes(1 as u64))
+	}
+    fn submit_api(f: u32, ) -> Weight {
         Weight::from_ref_time(66_168_000)
+            .saturating_add(Weight::from_ref_time(1_000_000).saturating_mul(f as u64))
             .saturating_add(RocksDbWeight::get().reads(3 as u64))
             .saturating_add(RocksDbWeight::get().writes(3 as u64))
+            // Counting the creator's feeds against `MaxFeedsPerCreator`
+            .saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(f as u64)))
+    }
+    fn submit_api_batch(c: u32, f: u32, ) -> Weight {
+        Weight::from_ref_time(66_168_000)
+            .saturating_add(Weight::from_ref_time(66_168_000).saturating_mul(c as u64))
+            .saturating_add(Weight::from_ref_time(1_000_000).saturating_mul((c as u64).saturating_mul(f as u64)))
+            .saturating_add(RocksDbWeight::get().reads((3 as u64).saturating_mul(c as u64)))
+            .saturating_add(RocksDbWeight::get().writes((3 as u64).saturating_mul(c as u64)))
+            // Counting the creator's feeds against `MaxFeedsPerCreator` for each entry
+            .saturating_add(RocksDbWeight::get().reads((c as u64).saturating_mul(f as u64)))
     }
     fn remove_api() -> Weight {
         Weight::from_ref_time(66_168_000)
             .saturating_add(RocksDbWeight::get().reads(3 as u64))
-            .saturating_add(RocksDbWeight::get().writes(3 as u64))
+            .saturating_add(RocksDbWeight::get().writes(4 as u64))
+    }
+    fn transfer_feed() -> Weight {
+        Weight::from_ref_time(66_168_000)
+            .saturating_add(RocksDbWeight::get().reads(6 as u64))
+            .saturating_add(RocksDbWeight::get().writes(7 as u64))
+    }
+    fn set_feed_boolean() -> Weight {
+        Weight::from_ref_time(30_000_000)
+            .saturating_add(RocksDbWeight::get().reads(1 as u64))
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
+    fn set_source_groups(c: u32, s: u32, ) -> Weight {
+        Weight::from_ref_time(15_000_000)
+            // Bounding and encoding every group and its sources
+            .saturating_add(Weight::from_ref_time(1_000_000).saturating_mul(c as u64))
+            .saturating_add(Weight::from_ref_time(200_000).saturating_mul(s as u64))
+            // SourceGroups
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
+    fn report_fetch_errors(c: u32, ) -> Weight {
+        Weight::from_ref_time(15_000_000)
+            .saturating_add(Weight::from_ref_time(5_000_000).saturating_mul(c as u64))
+            // Members
+            .saturating_add(RocksDbWeight::get().reads(1 as u64))
+            // Per error: feed and its health, and the feed again when it's disabled
+            .saturating_add(RocksDbWeight::get().reads((3 as u64).saturating_mul(c as u64)))
+            .saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(c as u64)))
+    }
+    fn set_fetching_enabled() -> Weight {
+        Weight::from_ref_time(15_000_000)
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
+    fn force_feed_value() -> Weight {
+        Weight::from_ref_time(20_000_000)
+            // Owner of the key and whether its feed is boolean
+            .saturating_add(RocksDbWeight::get().reads(2 as u64))
+            // Value, decimals, confidence and the forced flag
+            .saturating_add(RocksDbWeight::get().writes(4 as u64))
+    }
+    fn clear_forced_value() -> Weight {
+        Weight::from_ref_time(15_000_000)
+            // ForcedValues
+            .saturating_add(RocksDbWeight::get().reads(1 as u64))
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
+    fn set_combine_strategy() -> Weight {
+        Weight::from_ref_time(15_000_000)
+            // CombineStrategies
+            .saturating_add(RocksDbWeight::get().writes(1 as u64))
+    }
+    fn enable_feed() -> Weight {
+        Weight::from_ref_time(20_000_000)
+            .saturating_add(RocksDbWeight::get().reads(3 as u64))
+            .saturating_add(RocksDbWeight::get().writes(2 as u64))
+    }
+    fn deregister_operator(f: u32, r: u32, ) -> Weight {
+        Weight::from_ref_time(20_000_000)
+            .saturating_add(Weight::from_ref_time(10_000_000).saturating_mul(f as u64))
+            .saturating_add(Weight::from_ref_time(5_000_000).saturating_mul(r as u64))
+            .saturating_add(RocksDbWeight::get().reads_writes(1 as u64, 1 as u64))
+            .saturating_add(RocksDbWeight::get().reads((1 as u64).saturating_mul(f as u64)))
+            .saturating_add(RocksDbWeight::get().writes((3 as u64).saturating_mul(f as u64)))
+            .saturating_add(RocksDbWeight::get().writes((2 as u64).saturating_mul(r as u64)))
     }
 }
@@ -97,7 +97,7 @@ pub mod crypto {
 #[allow(non_camel_case_types)]
 enum KylinOracleFunc {
     #[codec(index = 1u8)]
-    xcm_feed_data { values: Vec<(Vec<u8>, i64)> },
+    xcm_feed_data { values: Vec<(Vec<u8>, i64)>, nonce: Option<u64> },
     #[codec(index = 2u8)]
     xcm_query_data { key: Vec<u8> },
 }
@@ -225,7 +225,7 @@ pub mod pallet {
         /// * `para_id` - parachain ID of the Oracle chain
 		/// * `values` - value array for the feed
 		/// 
-        #[pallet::weight(T::DbWeight::get().reads_writes(1,1).ref_time().saturating_add(10_000))]
+        #[pallet::weight(T::DbWeight::get().reads_writes(2,2).ref_time().saturating_add(10_000))]
         pub fn feed_data(
             origin: OriginFor<T>,
             para_id: ParaId,
@@ -359,6 +359,10 @@ pub mod pallet {
     #[pallet::getter(fn get_kylin_id)]
     pub(super) type KylinParaId<T: Config> = StorageValue<_, ParaId, OptionQuery>;
 
+    /// Nonce of the last feed sent to the Oracle chain, lets it drop redelivered messages
+    #[pallet::storage]
+    pub(super) type FeedNonce<T: Config> = StorageValue<_, u64, ValueQuery>;
+
     /// Storage map for the feed URL Endpoint
     #[pallet::storage]
 	#[pallet::getter(fn api_feeds)]
@@ -488,8 +492,13 @@ where T::AccountId: AsRef<[u8]>
     }
 
     fn feed_data_to_parachain(para_id: ParaId, values: Vec<(Vec<u8>, i64)>) -> DispatchResult {
+        let nonce = FeedNonce::<T>::mutate(|nonce| {
+            *nonce = nonce.wrapping_add(1);
+            *nonce
+        });
         let remark = KylinXcmCall::KylinOraclePallet(KylinOracleFunc::xcm_feed_data {
             values,
+            nonce: Some(nonce),
         });
         match T::XcmSender::send_xcm(
             (
@@ -39,8 +39,8 @@ pub use frame_support::{
     dispatch::DispatchClass,
     ensure, match_types, parameter_types,
     traits::{
-        ConstU128, ConstU32, ConstU64, Contains, EitherOfDiverse, EqualPrivilegeOnly, Everything,
-        IsInVec, Nothing, Randomness,
+        ConstBool, ConstU128, ConstU32, ConstU64, ConstU8, Contains, EitherOfDiverse,
+        EqualPrivilegeOnly, Everything, IsInVec, Nothing, Randomness,
     },
     weights::{
         constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
@@ -281,6 +281,8 @@ impl kylin_democracy::Config for Runtime {
 parameter_types! {
     pub const DistributionPalletId: PalletId = PalletId(*b"pdistrib");
     pub DistributionStake: Balance = 10 * Balance::from(10_u64.pow(18));
+    pub const DistributionMaxVestingPeriod: Moment = 2 * 365 * 24 * 60 * 60 * 1000;
+    pub const DistributionMemoLimit: u32 = 64;
     pub const DistributionPrefix: &'static [u8] = b"kylin-";
 }
 
@@ -294,6 +296,9 @@ impl kylin_distribution::Config for Runtime {
     type Time = Timestamp;
     type PalletId = DistributionPalletId;
     type Stake = DistributionStake;
+    type MaxVestingPeriod = DistributionMaxVestingPeriod;
+    type MemoLimit = DistributionMemoLimit;
+    type SponsorOrigin = EnsureRootOrHalfCouncil;
     type WeightInfo = kylin_distribution::weights::SubstrateWeight<Runtime>;
 }
 
@@ -598,6 +603,7 @@ parameter_types! {
     pub const GracePeriod: u32 = 5;
     pub const UnsignedInterval: u64 = 128;
     pub const UnsignedPriority: u64 = 1 << 20;
+    pub const OracleSubmissionMode: kylin_oracle::SubmissionMode = kylin_oracle::SubmissionMode::SignedOnly;
 }
 
 pub type LocalOriginToLocation = SignedToAccountId32<RuntimeOrigin, AccountId, RelayNetwork>;
@@ -656,6 +662,8 @@ impl kylin_oracle::Config for Runtime {
     type RuntimeCall = RuntimeCall;
     type RuntimeOrigin = RuntimeOrigin;
     type XcmSender = XcmRouter;
+    type FeedbackPalletIndex = ConstU8<168>;
+    type FeedbackCallIndex = ConstU8<7>;
     type UnsignedPriority = UnsignedPriority;
     type UnixTime = Timestamp;
     type WeightInfo = kylin_oracle::weights::SubstrateWeight<Runtime>;
@@ -665,7 +673,36 @@ impl kylin_oracle::Config for Runtime {
     type CombineData = DefaultCombineData<Self, ConstU32<1>, ConstU128<600>>;
     type Members = OracleProvider;
     type StrLimit = ConstU32<512>;
+    type MaxUrlLen = ConstU32<512>;
+    type MaxVPathLen = ConstU32<128>;
+    type MaxTagLen = ConstU32<16>;
+    type MaxAllowlistLen = ConstU32<16>;
     type MaxHasDispatchedSize = ConstU32<100>;
+    type MaxResponseBytes = ConstU32<{ 64 * 1024 }>;
+    type MaxRawValueAge = ConstU128<{ 60 * 60 * 1000 }>;
+    type MaxFeedsPerCreator = ConstU32<100>;
+    type MaxPendingResponses = ConstU32<100>;
+    type MaxResponseRetries = ConstU32<5>;
+    type OffchainFetchBudget = ConstU64<4_000>;
+    type MaxConcurrentFetches = ConstU32<8>;
+    type SignWithAllAccounts = ConstBool<false>;
+    type SubmissionMode = OracleSubmissionMode;
+    type MaxFeedNonces = ConstU32<64>;
+    type AdminOrigin = EnsureRootOrHalfCouncil;
+    type MaxSourceGroups = ConstU32<4>;
+    type MaxSourceGroupSize = ConstU32<16>;
+    type FetchInterval = ConstU32<1>;
+    type UpdateDeadband = ConstU64<0>;
+    type ClampOutOfBounds = ConstBool<false>;
+    type StrictAllOperators = ConstBool<false>;
+    type OnNewValue = ();
+    type FrozenRounds = ConstU32<0>;
+    type SuppressFrozen = ConstBool<false>;
+    type ValueSink = ();
+    type MaxConsecutiveFailures = ConstU32<100>;
+    type FetchFailureQuorum = ConstU32<2>;
+    type EmitDispersion = ConstBool<false>;
+    type StoreConfidence = ConstBool<true>;
 }
 
 parameter_types! {
@@ -1429,6 +1466,95 @@ impl_runtime_apis! {
         }
     }
 
+    impl kylin_oracle_runtime_api::KylinOracleApi<
+        Block,
+        Balance,
+        kylin_oracle::CreatorId<AccountId>,
+        kylin_oracle::ApiFeedOf<Runtime>,
+        kylin_oracle::ApiFeedInfo,
+        kylin_oracle::FeedHealth<AccountId, BlockNumber>,
+        kylin_oracle::TimestampedValue<i64, u128>,
+        kylin_oracle::OracleSnapshot<
+            kylin_oracle::CreatorId<AccountId>,
+            kylin_oracle::ApiFeedOf<Runtime>,
+            kylin_oracle::TimestampedValue<i64, u128>,
+        >,
+    > for Runtime {
+        fn feed_lags(now: u128) -> Vec<(Vec<u8>, u128)> {
+            KylinOraclePallet::feed_lags(now)
+        }
+
+        fn query_response_fee(key: Vec<u8>) -> Option<Balance> {
+            let key = key.try_into().ok()?;
+            Some(KylinOraclePallet::query_response_fee(key))
+        }
+
+        fn feeds_of(creator: kylin_oracle::CreatorId<AccountId>) -> Vec<(Vec<u8>, kylin_oracle::ApiFeedOf<Runtime>)> {
+            KylinOraclePallet::feeds_of(creator)
+                .into_iter()
+                .map(|(key, feed)| (key.into_inner(), feed))
+                .collect()
+        }
+
+        fn feed_config(
+            creator: kylin_oracle::CreatorId<AccountId>,
+            key: Vec<u8>,
+        ) -> Option<kylin_oracle::ApiFeedInfo> {
+            let key = key.try_into().ok()?;
+            KylinOraclePallet::feed_config(creator, &key)
+        }
+
+        fn oracle_diagnostics() -> Vec<(
+            kylin_oracle::CreatorId<AccountId>,
+            Vec<u8>,
+            kylin_oracle::FeedHealth<AccountId, BlockNumber>,
+        )> {
+            KylinOraclePallet::oracle_diagnostics()
+                .into_iter()
+                .map(|(creator, key, health)| (creator, key.into_inner(), health))
+                .collect()
+        }
+
+        fn get_with_raws(key: Vec<u8>) -> (
+            Option<kylin_oracle::TimestampedValue<i64, u128>>,
+            Vec<(kylin_oracle::CreatorId<AccountId>, kylin_oracle::TimestampedValue<i64, u128>)>,
+        ) {
+            match key.try_into() {
+                Ok(key) => KylinOraclePallet::get_with_raws(&key),
+                Err(_) => (None, Vec::new()),
+            }
+        }
+
+        fn get_with_confidence(key: Vec<u8>) -> Option<(kylin_oracle::TimestampedValue<i64, u128>, Option<u32>)> {
+            let key = key.try_into().ok()?;
+            let (value, confidence) = KylinOraclePallet::get_with_confidence(&key)?;
+            Some((value, confidence.map(|confidence| confidence.deconstruct())))
+        }
+
+        fn get_with_pair(key: Vec<u8>) -> Option<(
+            kylin_oracle::TimestampedValue<i64, u128>,
+            Option<(Vec<u8>, Vec<u8>)>,
+        )> {
+            let key = key.try_into().ok()?;
+            let (value, pair) = KylinOraclePallet::get_with_pair(&key)?;
+            Some((value, pair.map(|pair| (pair.base.into_inner(), pair.quote.into_inner()))))
+        }
+
+        fn snapshot(from: Option<Vec<u8>>, max_keys: u32) -> kylin_oracle::OracleSnapshot<
+            kylin_oracle::CreatorId<AccountId>,
+            kylin_oracle::ApiFeedOf<Runtime>,
+            kylin_oracle::TimestampedValue<i64, u128>,
+        > {
+            KylinOraclePallet::snapshot(from, max_keys)
+        }
+    }
+
+    impl kylin_distribution_runtime_api::DistributionApi<Block, AccountId, DistributionId> for Runtime {
+        fn distributions_for(identity: AccountId) -> Vec<DistributionId> {
+            KylinDistribution::distributions_for(identity)
+        }
+    }
+
     impl cumulus_primitives_core::CollectCollationInfo<Block> for Runtime {
         fn collect_collation_info(header: &<Block as BlockT>::Header) -> cumulus_primitives_core::CollationInfo {
             ParachainSystem::collect_collation_info(header)